@@ -0,0 +1,3 @@
+ALTER TABLE feedback ADD COLUMN instance_id TEXT; -- id embedded in the card actions
+
+CREATE UNIQUE INDEX IX_FEEDBACK_INSTANCE_ID ON feedback (instance_id);
@@ -0,0 +1 @@
+ALTER TABLE feedback ADD COLUMN title TEXT; -- title given to the feedback command, if any
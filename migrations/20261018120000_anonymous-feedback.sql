@@ -0,0 +1,10 @@
+ALTER TABLE feedback ADD COLUMN anonymous BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Identifies the respondent of an entry : the user id, or a keyed hash of it for anonymous feedbacks
+ALTER TABLE feedback_entry ADD COLUMN respondent_key TEXT;
+UPDATE feedback_entry SET respondent_key = user_id;
+ALTER TABLE feedback_entry ALTER COLUMN respondent_key SET NOT NULL;
+
+ALTER TABLE feedback_entry DROP CONSTRAINT PK_FEEDBACK_ENTRY_FEEDBACK_ID_USER_ID;
+ALTER TABLE feedback_entry ADD CONSTRAINT PK_FEEDBACK_ENTRY_FEEDBACK_ID_RESPONDENT_KEY PRIMARY KEY (feedback_id, respondent_key);
+ALTER TABLE feedback_entry ALTER COLUMN user_id DROP NOT NULL;
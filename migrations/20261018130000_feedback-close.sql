@@ -0,0 +1,6 @@
+ALTER TABLE feedback ADD COLUMN conversation_id TEXT; -- conversation the card was sent to
+ALTER TABLE feedback ADD COLUMN service_url TEXT; -- service url of that conversation, to update the card
+ALTER TABLE feedback ADD COLUMN closes_at TIMESTAMPTZ; -- deadline given to the feedback command, if any
+ALTER TABLE feedback ADD COLUMN closed_at TIMESTAMPTZ;
+
+CREATE INDEX IX_FEEDBACK_CLOSES_AT ON feedback (closes_at) WHERE closed_at IS NULL;
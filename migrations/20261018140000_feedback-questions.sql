@@ -0,0 +1,22 @@
+ALTER TABLE feedback ADD COLUMN template TEXT; -- name of the template the questions come from, if any
+
+-- Copy of the questions of the template, so the reports do not depend on the templates shipped with the bot
+CREATE TABLE feedback_question (
+    feedback_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    kind TEXT NOT NULL, -- rating, yesNo, choice or text
+    label TEXT NOT NULL,
+    choices TEXT[] NOT NULL DEFAULT '{}',
+    CONSTRAINT PK_FEEDBACK_QUESTION_FEEDBACK_ID_POSITION PRIMARY KEY (feedback_id, position),
+    CONSTRAINT FK_FEEDBACK_QUESTION_FEEDBACK_ID FOREIGN KEY (feedback_id) REFERENCES feedback(id)
+);
+
+CREATE TABLE feedback_answer (
+    feedback_id TEXT NOT NULL,
+    respondent_key TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    CONSTRAINT PK_FEEDBACK_ANSWER_FEEDBACK_ID_RESPONDENT_KEY_POSITION PRIMARY KEY (feedback_id, respondent_key, position),
+    CONSTRAINT FK_FEEDBACK_ANSWER_FEEDBACK_ID_RESPONDENT_KEY FOREIGN KEY (feedback_id, respondent_key) REFERENCES feedback_entry(feedback_id, respondent_key),
+    CONSTRAINT FK_FEEDBACK_ANSWER_FEEDBACK_ID_POSITION FOREIGN KEY (feedback_id, position) REFERENCES feedback_question(feedback_id, position)
+);
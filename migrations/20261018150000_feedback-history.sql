@@ -0,0 +1,5 @@
+-- When the request was sent, to order the sessions of a conversation. The feedbacks sent before this migration get its date.
+ALTER TABLE feedback ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+-- A recurring meeting keeps the same chat, so its sessions are the feedbacks of a conversation
+CREATE INDEX IX_FEEDBACK_CONVERSATION_ID_CREATED_AT ON feedback (conversation_id, created_at DESC);
@@ -0,0 +1,8 @@
+-- Rows created before this migration get its date
+ALTER TABLE "user" ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE "user" ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+ALTER TABLE feedback ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+ALTER TABLE feedback_entry ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(); -- when the vote was first cast
+ALTER TABLE feedback_entry ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(); -- when the vote was last changed
@@ -0,0 +1,18 @@
+-- Where a message of the bot was posted, to update it or follow up on it outside of any incoming activity
+CREATE TABLE conversation_reference (
+    activity_id TEXT NOT NULL, -- id of the message posted by the bot
+    conversation_id TEXT NOT NULL,
+    conversation_type TEXT,
+    tenant_id TEXT,
+    service_url TEXT,
+    channel_id TEXT,
+    bot_id TEXT,
+    bot_name TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT PK_CONVERSATION_REFERENCE_ACTIVITY_ID PRIMARY KEY (activity_id)
+);
+
+INSERT INTO conversation_reference (activity_id, conversation_id, service_url)
+SELECT id, conversation_id, service_url FROM feedback WHERE conversation_id IS NOT NULL;
+
+ALTER TABLE feedback DROP COLUMN service_url;
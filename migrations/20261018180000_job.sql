@@ -0,0 +1,27 @@
+-- Actions to run later, executed at least once by whichever instance of the bot leases them first
+CREATE TABLE job (
+    id TEXT NOT NULL,
+    kind TEXT NOT NULL, -- selects the handler of the job
+    payload JSONB NOT NULL,
+    run_at TIMESTAMPTZ NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL,
+    locked_by TEXT, -- instance running the job
+    locked_until TIMESTAMPTZ, -- after which the job is considered abandoned and run again
+    last_error TEXT,
+    completed_at TIMESTAMPTZ,
+    failed_at TIMESTAMPTZ, -- set once every attempt failed
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT PK_JOB_ID PRIMARY KEY (id)
+);
+
+CREATE INDEX IX_JOB_RUN_AT ON job (run_at) WHERE completed_at IS NULL AND failed_at IS NULL;
+
+-- The deadlines were checked periodically until now, they become jobs
+INSERT INTO job (id, kind, payload, run_at, max_attempts)
+SELECT gen_random_uuid()::TEXT, 'feedbackDeadline', jsonb_build_object('instanceId', instance_id), closes_at, 5
+FROM feedback
+WHERE closes_at IS NOT NULL AND closed_at IS NULL AND instance_id IS NOT NULL;
+
+DROP INDEX IX_FEEDBACK_CLOSES_AT;
@@ -0,0 +1,3 @@
+ALTER TABLE feedback ADD COLUMN meeting_id TEXT; -- online meeting the feedback was requested in, if any
+ALTER TABLE feedback ADD COLUMN organizer_id TEXT; -- Entra ID object id of the organizer of the meeting
+ALTER TABLE feedback ADD COLUMN organizer_name TEXT;
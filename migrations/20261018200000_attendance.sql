@@ -0,0 +1,25 @@
+-- Sessions of the online meetings the feedbacks were requested in, read from their Graph attendance reports
+CREATE TABLE attendance_report (
+    id TEXT NOT NULL,
+    meeting_id TEXT NOT NULL,
+    started_at TIMESTAMPTZ,
+    ended_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT PK_ATTENDANCE_REPORT_ID PRIMARY KEY (id)
+);
+
+CREATE INDEX IX_ATTENDANCE_REPORT_MEETING_ID ON attendance_report (meeting_id);
+
+-- Users who attended a session, from their first join to their last leave
+CREATE TABLE attendance (
+    report_id TEXT NOT NULL,
+    user_id TEXT NOT NULL, -- Entra ID object id of the attendee
+    name TEXT,
+    email TEXT,
+    role TEXT,
+    joined_at TIMESTAMPTZ,
+    left_at TIMESTAMPTZ,
+    duration INTEGER NOT NULL, -- total attendance in seconds
+    CONSTRAINT PK_ATTENDANCE PRIMARY KEY (report_id, user_id),
+    CONSTRAINT FK_ATTENDANCE_REPORT_ID FOREIGN KEY (report_id) REFERENCES attendance_report(id) ON DELETE CASCADE
+);
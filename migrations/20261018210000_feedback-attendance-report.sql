@@ -0,0 +1 @@
+ALTER TABLE feedback ADD COLUMN attendance_report_id TEXT; -- session of the meeting the feedback is about, once its attendance is known
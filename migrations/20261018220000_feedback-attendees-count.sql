@@ -0,0 +1 @@
+ALTER TABLE feedback ADD COLUMN attendees_count INTEGER; -- attendees of the session of the feedback, cached with attendance_report_id
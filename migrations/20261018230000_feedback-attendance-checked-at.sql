@@ -0,0 +1 @@
+ALTER TABLE feedback ADD COLUMN attendance_checked_at TIMESTAMPTZ; -- last time the attendance reports were fetched without finding the session of the feedback
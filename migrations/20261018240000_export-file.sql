@@ -0,0 +1,8 @@
+-- Exports offered through a file consent card, kept until the user answers so the uploaded file is the one announced
+CREATE TABLE export_file (
+    id TEXT NOT NULL,
+    owner_id TEXT NOT NULL,
+    content BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT PK_EXPORT_FILE_ID PRIMARY KEY (id)
+);
@@ -0,0 +1 @@
+ALTER TABLE feedback ADD COLUMN locale TEXT NOT NULL DEFAULT 'fr'; -- language of the owner, in which the cards of the feedback are written
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts},
+    http::{request::Parts, HeaderMap},
+};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+use crate::error::{Error, Result};
+
+/// The header holding the key of the internal endpoints.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Validates the key sent to the endpoints called by other services rather than by Teams.
+#[derive(Clone)]
+pub struct ApiKeyAuthenticator {
+    key: Arc<str>,
+}
+
+impl ApiKeyAuthenticator {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: Arc::from(key),
+        }
+    }
+
+    /// Checks the key of the request. The digests are compared in constant time, so the key cannot be guessed from the response times.
+    pub fn validate(&self, headers: &HeaderMap) -> Result<()> {
+        let key = headers
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| Error::Unauthorized("Missing API key.".to_owned()))?;
+
+        let expected = Sha256::digest(self.key.as_bytes());
+        let actual = Sha256::digest(key.as_bytes());
+
+        match bool::from(expected.ct_eq(&actual)) {
+            true => Ok(()),
+            false => Err(Error::Unauthorized("Invalid API key.".to_owned())),
+        }
+    }
+}
+
+/// Guards a handler with the [`ApiKeyAuthenticator`].
+pub struct ApiKeyAuthenticated;
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ApiKeyAuthenticated
+where
+    S: Send + Sync,
+    ApiKeyAuthenticator: FromRef<S>,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        ApiKeyAuthenticator::from_ref(state).validate(&parts.headers)?;
+
+        Ok(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Some("secret"), true)]
+    #[case(Some("secrets"), false)]
+    #[case(Some(""), false)]
+    #[case(None, false)]
+    fn test_validate(#[case] key: Option<&str>, #[case] expected: bool) {
+        // Arrange
+        let authenticator = ApiKeyAuthenticator::new("secret");
+        let mut headers = HeaderMap::new();
+        if let Some(key) = key {
+            headers.insert(API_KEY_HEADER, HeaderValue::from_str(key).unwrap());
+        }
+
+        // Act
+        let result = authenticator.validate(&headers);
+
+        // Assert
+        assert_eq!(expected, result.is_ok());
+    }
+}
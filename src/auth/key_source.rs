@@ -0,0 +1,174 @@
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use jsonwebtoken::{jwk::Jwk, DecodingKey};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::error::{Error, Result};
+
+const OPENID_METADATA_URL: &str =
+    "https://login.botframework.com/v1/.well-known/openidconfiguration";
+
+/// Bot Framework recommends caching the signing keys for no more than 24 hours.
+const KEYS_LIFETIME: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Minimum delay between two refreshes triggered by an unknown key id, so a forged token cannot be used to hammer the metadata endpoint.
+const REFRESH_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+/// A key used to verify the signature of the tokens sent by the Bot Framework.
+#[derive(Clone)]
+pub struct SigningKey {
+    pub key: DecodingKey,
+    /// The channels (e.g. `msteams`) allowed to use this key. An empty list means any channel.
+    pub endorsements: Vec<String>,
+}
+
+/// Provides the keys used to verify the inbound tokens.
+#[async_trait]
+pub trait KeySource: Send + Sync {
+    /// Returns the key identified by `kid`, or `None` if it is not known.
+    async fn get_key(&self, kid: &str) -> Result<Option<SigningKey>>;
+}
+
+/// A fixed set of keys, mostly useful to validate locally generated tokens.
+#[derive(Clone, Default)]
+pub struct StaticKeySource {
+    keys: Vec<(String, SigningKey)>,
+}
+
+impl StaticKeySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_key(mut self, kid: &str, key: SigningKey) -> Self {
+        self.keys.push((kid.to_owned(), key));
+        self
+    }
+}
+
+#[async_trait]
+impl KeySource for StaticKeySource {
+    async fn get_key(&self, kid: &str) -> Result<Option<SigningKey>> {
+        Ok(self
+            .keys
+            .iter()
+            .find(|(id, _)| id == kid)
+            .map(|(_, key)| key.clone()))
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenIdMetadata {
+    jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct KeySet {
+    keys: Vec<BotJwk>,
+}
+
+#[derive(Deserialize)]
+struct BotJwk {
+    #[serde(flatten)]
+    jwk: Jwk,
+    #[serde(default)]
+    endorsements: Vec<String>,
+}
+
+struct CachedKeys {
+    keys: Vec<(String, SigningKey)>,
+    fetched: Instant,
+}
+
+/// Fetches the keys from the Bot Framework OpenID metadata and keeps them in cache.
+pub struct OpenIdKeySource {
+    client: reqwest::Client,
+    metadata_url: String,
+    cache: RwLock<Option<CachedKeys>>,
+}
+
+impl OpenIdKeySource {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self::with_metadata_url(client, OPENID_METADATA_URL)
+    }
+
+    pub fn with_metadata_url(client: reqwest::Client, metadata_url: &str) -> Self {
+        Self {
+            client,
+            metadata_url: metadata_url.to_owned(),
+            cache: RwLock::new(None),
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn fetch_keys(&self) -> Result<Vec<(String, SigningKey)>> {
+        let result = self.client.get(&self.metadata_url).send().await?;
+        if !result.status().is_success() {
+            return Err(Error::Service(result.json().await?));
+        }
+        let metadata: OpenIdMetadata = result.json().await?;
+
+        let result = self.client.get(&metadata.jwks_uri).send().await?;
+        if !result.status().is_success() {
+            return Err(Error::Service(result.json().await?));
+        }
+        let key_set: KeySet = result.json().await?;
+
+        let keys = key_set
+            .keys
+            .into_iter()
+            .filter_map(|BotJwk { jwk, endorsements }| {
+                let kid = jwk.common.key_id.clone()?;
+                match DecodingKey::from_jwk(&jwk) {
+                    Ok(key) => Some((kid, SigningKey { key, endorsements })),
+                    Err(e) => {
+                        warn!("Ignoring the signing key `{kid}` : {:?}", e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Ok(keys)
+    }
+}
+
+#[async_trait]
+impl KeySource for OpenIdKeySource {
+    async fn get_key(&self, kid: &str) -> Result<Option<SigningKey>> {
+        {
+            let cache = self.cache.read().await;
+            if let Some(ref cache) = *cache {
+                let key = cache.keys.iter().find(|(id, _)| id == kid);
+                let elapsed = cache.fetched.elapsed();
+                match key {
+                    Some((_, key)) if elapsed < KEYS_LIFETIME => return Ok(Some(key.clone())),
+                    None if elapsed < REFRESH_COOLDOWN => return Ok(None),
+                    _ => (),
+                }
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+
+        // Another request may have refreshed the keys while we were waiting for the lock.
+        let is_fresh = matches!(*cache, Some(ref c) if c.fetched.elapsed() < REFRESH_COOLDOWN);
+        if !is_fresh {
+            *cache = Some(CachedKeys {
+                keys: self.fetch_keys().await?,
+                fetched: Instant::now(),
+            });
+        }
+
+        Ok(cache.as_ref().and_then(|cache| {
+            cache
+                .keys
+                .iter()
+                .find(|(id, _)| id == kid)
+                .map(|(_, key)| key.clone())
+        }))
+    }
+}
@@ -0,0 +1,269 @@
+pub mod api_key;
+pub mod key_source;
+
+pub use api_key::{ApiKeyAuthenticated, ApiKeyAuthenticator};
+pub use key_source::{KeySource, OpenIdKeySource, SigningKey, StaticKeySource};
+
+use std::sync::Arc;
+
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequest, Request},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use jsonwebtoken::{Algorithm, Validation};
+use serde::Deserialize;
+
+use crate::{
+    error::{Error, Result},
+    models::Activity,
+};
+
+const ISSUER: &str = "https://api.botframework.com";
+
+/// Tolerated clock skew between the Bot Framework and us, as recommended by the Bot Framework documentation.
+const LEEWAY_SECONDS: u64 = 5 * 60;
+
+#[derive(Debug, Deserialize)]
+pub struct Claims {
+    #[serde(alias = "serviceUrl", rename = "serviceurl")]
+    pub service_url: Option<String>,
+}
+
+/// Validates the bearer tokens the Bot Framework attaches to every activity it sends to the bot.
+#[derive(Clone)]
+pub struct BotAuthenticator {
+    key_source: Arc<dyn KeySource>,
+    audience: String,
+}
+
+impl BotAuthenticator {
+    pub fn new(key_source: Arc<dyn KeySource>, audience: &str) -> Self {
+        Self {
+            key_source,
+            audience: audience.to_owned(),
+        }
+    }
+
+    /// Checks the signature, issuer, audience and lifetime of the token, then makes sure it was issued for the channel and service url of the activity.
+    #[tracing::instrument(skip_all)]
+    pub async fn validate(&self, token: &str, activity: &Activity) -> Result<Claims> {
+        let header = jsonwebtoken::decode_header(token)
+            .map_err(|e| Error::Unauthorized(format!("Malformed token ({e})")))?;
+        let kid = header
+            .kid
+            .ok_or_else(|| Error::Unauthorized("The token has no key id.".to_owned()))?;
+
+        let signing_key = self
+            .key_source
+            .get_key(&kid)
+            .await?
+            .ok_or_else(|| Error::Unauthorized(format!("Unknown signing key `{kid}`.")))?;
+
+        if !signing_key.endorsements.is_empty() {
+            let Some(ref channel_id) = activity.channel_id else {
+                return Err(Error::Unauthorized(
+                    "The activity has no channel, while the signing key is endorsed for some."
+                        .to_owned(),
+                ));
+            };
+            if !signing_key.endorsements.contains(channel_id) {
+                return Err(Error::Unauthorized(format!(
+                    "The signing key is not endorsed for the channel `{channel_id}`."
+                )));
+            }
+        }
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[ISSUER]);
+        validation.set_audience(&[&self.audience]);
+        validation.leeway = LEEWAY_SECONDS;
+
+        let claims = jsonwebtoken::decode::<Claims>(token, &signing_key.key, &validation)
+            .map_err(|e| Error::Unauthorized(format!("Invalid token ({e})")))?
+            .claims;
+
+        let expected = activity
+            .service_url
+            .as_deref()
+            .map(|x| x.trim_end_matches('/'));
+        let actual = claims
+            .service_url
+            .as_deref()
+            .map(|x| x.trim_end_matches('/'));
+        if expected != actual {
+            return Err(Error::Unauthorized(
+                "The service url of the token does not match the activity.".to_owned(),
+            ));
+        }
+
+        Ok(claims)
+    }
+}
+
+/// An [`Activity`] whose bearer token has been validated by the [`BotAuthenticator`].
+pub struct AuthenticatedActivity(pub Activity);
+
+#[async_trait]
+impl<S> FromRequest<S> for AuthenticatedActivity
+where
+    S: Send + Sync,
+    BotAuthenticator: FromRef<S>,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> std::result::Result<Self, Self::Rejection> {
+        let authenticator = BotAuthenticator::from_ref(state);
+
+        let token = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| token.trim().to_owned())
+            .ok_or_else(|| {
+                Error::Unauthorized("Missing bearer token.".to_owned()).into_response()
+            })?;
+
+        let Json(activity) = Json::<Activity>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        authenticator
+            .validate(&token, &activity)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        Ok(Self(activity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use jsonwebtoken::{DecodingKey, EncodingKey, Header};
+    use rstest::rstest;
+
+    use super::*;
+
+    const PRIVATE_KEY: &str = include_str!("../assets/tests/private_key.pem");
+    const PUBLIC_KEY: &str = include_str!("../assets/tests/public_key.pem");
+    const KID: &str = "test-key";
+    const AUDIENCE: &str = "bot-client-id";
+    const SERVICE_URL: &str = "https://smba.trafficmanager.net/emea/";
+
+    fn authenticator(endorsements: &[&str]) -> BotAuthenticator {
+        let key_source = StaticKeySource::new().with_key(
+            KID,
+            SigningKey {
+                key: DecodingKey::from_rsa_pem(PUBLIC_KEY.as_bytes()).unwrap(),
+                endorsements: endorsements.iter().map(|x| x.to_string()).collect(),
+            },
+        );
+
+        BotAuthenticator::new(Arc::new(key_source), AUDIENCE)
+    }
+
+    fn token(
+        kid: &str,
+        issuer: &str,
+        audience: &str,
+        expires_in: i64,
+        service_url: &str,
+    ) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(kid.to_owned());
+
+        jsonwebtoken::encode(
+            &header,
+            &serde_json::json!({
+                "iss": issuer,
+                "aud": audience,
+                "exp": now + expires_in,
+                "nbf": now - 60,
+                "serviceurl": service_url,
+            }),
+            &EncodingKey::from_rsa_pem(PRIVATE_KEY.as_bytes()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn activity() -> Activity {
+        Activity {
+            channel_id: Some("msteams".to_owned()),
+            service_url: Some(SERVICE_URL.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case(KID, ISSUER, AUDIENCE, 3600, SERVICE_URL, &[], true)]
+    #[case(KID, ISSUER, AUDIENCE, 3600, SERVICE_URL, &["msteams"], true)]
+    #[case(KID, ISSUER, AUDIENCE, 3600, "https://smba.trafficmanager.net/emea", &[], true)]
+    #[case(KID, ISSUER, AUDIENCE, 3600, SERVICE_URL, &["skype"], false)]
+    #[case("unknown", ISSUER, AUDIENCE, 3600, SERVICE_URL, &[], false)]
+    #[case(KID, "https://sts.windows.net/", AUDIENCE, 3600, SERVICE_URL, &[], false)]
+    #[case(KID, ISSUER, "another-bot", 3600, SERVICE_URL, &[], false)]
+    #[case(KID, ISSUER, AUDIENCE, -3600, SERVICE_URL, &[], false)]
+    #[case(KID, ISSUER, AUDIENCE, 3600, "https://evil.example.com/", &[], false)]
+    #[tokio::test]
+    async fn test_validate(
+        #[case] kid: &str,
+        #[case] issuer: &str,
+        #[case] audience: &str,
+        #[case] expires_in: i64,
+        #[case] service_url: &str,
+        #[case] endorsements: &[&str],
+        #[case] expected: bool,
+    ) {
+        // Arrange
+        let authenticator = authenticator(endorsements);
+        let token = token(kid, issuer, audience, expires_in, service_url);
+
+        // Act
+        let result = authenticator.validate(&token, &activity()).await;
+
+        // Assert
+        assert_eq!(expected, result.is_ok(), "{:?}", result.err());
+    }
+
+    #[rstest]
+    #[case(&[], true)]
+    #[case(&["msteams"], false)]
+    #[tokio::test]
+    async fn test_validate_without_channel(#[case] endorsements: &[&str], #[case] expected: bool) {
+        // Arrange
+        let authenticator = authenticator(endorsements);
+        let token = token(KID, ISSUER, AUDIENCE, 3600, SERVICE_URL);
+        let activity = Activity {
+            channel_id: None,
+            ..activity()
+        };
+
+        // Act
+        let result = authenticator.validate(&token, &activity).await;
+
+        // Assert
+        assert_eq!(expected, result.is_ok(), "{:?}", result.err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_malformed_token() {
+        // Arrange
+        let authenticator = authenticator(&[]);
+
+        // Act
+        let result = authenticator.validate("not-a-token", &activity()).await;
+
+        // Assert
+        assert!(matches!(result, Err(Error::Unauthorized(_))));
+    }
+}
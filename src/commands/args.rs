@@ -0,0 +1,544 @@
+use std::{str::FromStr, time::Duration};
+
+use thiserror::Error;
+
+use crate::i18n::Locale;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ParseError {
+    #[error("The quote opened at position {0} is never closed.")]
+    UnterminatedQuote(usize),
+
+    #[error("The mention opened at position {0} is never closed.")]
+    UnterminatedMention(usize),
+
+    #[error("The option `--{0}` does not exist for this command.")]
+    UnknownFlag(String),
+
+    #[error("The option `--{0}` expects a value.")]
+    MissingFlagValue(String),
+
+    #[error("The value `{value}` is not valid for `{name}` : expected {expected}.")]
+    InvalidValue {
+        name: String,
+        value: String,
+        expected: &'static str,
+        expected_fr: &'static str,
+    },
+
+    #[error("The argument `{0}` was not expected.")]
+    UnexpectedArgument(String),
+
+    #[error("The argument `{0}` is required.")]
+    MissingArgument(&'static str),
+}
+
+impl ParseError {
+    /// The message explaining the error to the user.
+    pub fn user_message(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (_, Locale::English) => self.to_string(),
+            (ParseError::UnterminatedQuote(position), Locale::French) => {
+                format!("Le guillemet ouvert à la position {position} n'est jamais fermé.")
+            }
+            (ParseError::UnterminatedMention(position), Locale::French) => {
+                format!("La mention ouverte à la position {position} n'est jamais fermée.")
+            }
+            (ParseError::UnknownFlag(flag), Locale::French) => {
+                format!("L'option `--{flag}` n'existe pas pour cette commande.")
+            }
+            (ParseError::MissingFlagValue(flag), Locale::French) => {
+                format!("L'option `--{flag}` attend une valeur.")
+            }
+            (
+                ParseError::InvalidValue {
+                    name,
+                    value,
+                    expected_fr,
+                    ..
+                },
+                Locale::French,
+            ) => format!("La valeur `{value}` n'est pas valide pour `{name}`, valeur attendue : {expected_fr}."),
+            (ParseError::UnexpectedArgument(argument), Locale::French) => {
+                format!("L'argument `{argument}` n'était pas attendu.")
+            }
+            (ParseError::MissingArgument(argument), Locale::French) => {
+                format!("L'argument `{argument}` est obligatoire.")
+            }
+        }
+    }
+}
+
+/// A value which can be read from a command argument.
+pub trait FromArgument: Sized {
+    /// Describes the expected format, used in the error messages.
+    const EXPECTED: &'static str;
+    /// Same as [`FromArgument::EXPECTED`], in French.
+    const EXPECTED_FR: &'static str;
+
+    fn from_argument(value: &str) -> Option<Self>;
+}
+
+impl FromArgument for String {
+    const EXPECTED: &'static str = "a text";
+    const EXPECTED_FR: &'static str = "un texte";
+
+    fn from_argument(value: &str) -> Option<Self> {
+        Some(value.to_owned())
+    }
+}
+
+impl FromArgument for i64 {
+    const EXPECTED: &'static str = "an integer";
+    const EXPECTED_FR: &'static str = "un nombre entier";
+
+    fn from_argument(value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+impl FromArgument for u32 {
+    const EXPECTED: &'static str = "a positive integer";
+    const EXPECTED_FR: &'static str = "un nombre entier positif";
+
+    fn from_argument(value: &str) -> Option<Self> {
+        value.parse().ok()
+    }
+}
+
+impl FromArgument for Duration {
+    const EXPECTED: &'static str = "a duration such as `30m`, `2h` or `1h30m`";
+    const EXPECTED_FR: &'static str = "une durée comme `30m`, `2h` ou `1h30m`";
+
+    /// Parses a sequence of `<number><unit>` where the unit is one of `d`, `h`, `m` or `s`.
+    fn from_argument(value: &str) -> Option<Self> {
+        let mut total = 0u64;
+        let mut number = String::new();
+
+        for c in value.chars() {
+            match c {
+                '0'..='9' => number.push(c),
+                'd' | 'h' | 'm' | 's' => {
+                    let amount: u64 = number.parse().ok()?;
+                    let unit = match c {
+                        'd' => 24 * 60 * 60,
+                        'h' => 60 * 60,
+                        'm' => 60,
+                        _ => 1,
+                    };
+                    total = total.checked_add(amount.checked_mul(unit)?)?;
+                    number.clear();
+                }
+                _ => return None,
+            }
+        }
+
+        match (number.is_empty(), total) {
+            (true, total) if total > 0 => Some(Duration::from_secs(total)),
+            _ => None,
+        }
+    }
+}
+
+/// A user mentioned in the command, e.g. `<at>John Doe</at>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mention {
+    pub name: String,
+}
+
+impl FromArgument for Mention {
+    const EXPECTED: &'static str = "a mention such as `@John Doe`";
+    const EXPECTED_FR: &'static str = "une mention comme `@John Doe`";
+
+    fn from_argument(value: &str) -> Option<Self> {
+        value
+            .strip_prefix("<at>")
+            .and_then(|x| x.strip_suffix("</at>"))
+            .map(|name| Mention {
+                name: name.trim().to_owned(),
+            })
+    }
+}
+
+/// A word of a command line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub value: String,
+    /// Whether the value was quoted, in which case it is never read as a subcommand or a flag.
+    pub quoted: bool,
+}
+
+impl Token {
+    /// Returns the value, unless the token was quoted.
+    fn bare(&self) -> Option<&str> {
+        match self.quoted {
+            true => None,
+            false => Some(&self.value),
+        }
+    }
+
+    fn is_flag(&self) -> bool {
+        self.bare().is_some_and(|x| x.starts_with("--"))
+    }
+}
+
+/// Splits a command line into tokens. Quoted values (`"..."`, `'...'` or `“...”`) and mentions (`<at>...</at>`) are kept as a single token.
+pub fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        if input[start..].starts_with("<at>") {
+            let end = input[start..]
+                .find("</at>")
+                .ok_or(ParseError::UnterminatedMention(start))?;
+            let end = start + end + "</at>".len();
+            tokens.push(Token {
+                value: input[start..end].to_owned(),
+                quoted: false,
+            });
+            while chars.next_if(|&(i, _)| i < end).is_some() {}
+            continue;
+        }
+
+        let closing = match c {
+            '"' => Some('"'),
+            '\'' => Some('\''),
+            '“' => Some('”'),
+            _ => None,
+        };
+
+        let mut token = String::new();
+        match closing {
+            Some(closing) => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some((_, '\\')) => {
+                            if let Some((_, escaped)) = chars.next() {
+                                token.push(escaped);
+                            }
+                        }
+                        Some((_, c)) if c == closing => break,
+                        Some((_, c)) => token.push(c),
+                        None => return Err(ParseError::UnterminatedQuote(start)),
+                    }
+                }
+            }
+            None => {
+                while let Some((_, c)) = chars.next_if(|&(_, c)| !c.is_whitespace()) {
+                    token.push(c);
+                }
+            }
+        }
+        tokens.push(Token {
+            value: token,
+            quoted: closing.is_some(),
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// Reads the arguments of a command. Flags and options must be read before the positional arguments, and every value must be consumed before calling [`Arguments::finish`], which rejects anything left.
+#[derive(Debug)]
+pub struct Arguments {
+    tokens: Vec<Option<Token>>,
+}
+
+impl Arguments {
+    pub fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens: tokens.into_iter().map(Some).collect(),
+        }
+    }
+
+    fn find_flag(&self, name: &str) -> Option<usize> {
+        self.tokens.iter().position(|token| {
+            token
+                .as_ref()
+                .and_then(Token::bare)
+                .and_then(|token| token.strip_prefix("--"))
+                .is_some_and(|token| token == name)
+        })
+    }
+
+    /// Returns whether the next argument is the subcommand `name`, ignoring the case, unless it was quoted. Subcommands must be read before the flags and the options.
+    pub fn subcommand(&mut self, name: &str) -> bool {
+        match self.tokens.iter_mut().find(|x| x.is_some()) {
+            Some(token)
+                if token
+                    .as_ref()
+                    .and_then(Token::bare)
+                    .is_some_and(|x| x.eq_ignore_ascii_case(name)) =>
+            {
+                *token = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns whether the flag `--{name}` is present.
+    pub fn flag(&mut self, name: &str) -> bool {
+        match self.find_flag(name) {
+            Some(index) => {
+                self.tokens[index] = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the value following the option `--{name}`, if present.
+    pub fn option<T: FromArgument>(&mut self, name: &str) -> Result<Option<T>, ParseError> {
+        let Some(index) = self.find_flag(name) else {
+            return Ok(None);
+        };
+        self.tokens[index] = None;
+
+        let value = self
+            .tokens
+            .get_mut(index + 1)
+            .filter(|token| token.as_ref().is_some_and(|x| !x.is_flag()))
+            .and_then(Option::take)
+            .ok_or_else(|| ParseError::MissingFlagValue(name.to_owned()))?;
+
+        parse_value(&format!("--{name}"), &value.value).map(Some)
+    }
+
+    /// Returns the next positional argument, if any.
+    pub fn positional<T: FromArgument>(
+        &mut self,
+        name: &'static str,
+    ) -> Result<Option<T>, ParseError> {
+        let value = self
+            .tokens
+            .iter_mut()
+            .find(|token| token.as_ref().is_some_and(|x| !x.is_flag()))
+            .and_then(Option::take);
+
+        match value {
+            Some(value) => parse_value(name, &value.value).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the next positional argument, failing if there is none.
+    pub fn required<T: FromArgument>(&mut self, name: &'static str) -> Result<T, ParseError> {
+        self.positional(name)?
+            .ok_or(ParseError::MissingArgument(name))
+    }
+
+    /// Fails if some arguments were not consumed by the command.
+    pub fn finish(self) -> Result<(), ParseError> {
+        match self.tokens.into_iter().flatten().next() {
+            Some(token) => match token.bare().and_then(|x| x.strip_prefix("--")) {
+                Some(flag) => Err(ParseError::UnknownFlag(flag.to_owned())),
+                None => Err(ParseError::UnexpectedArgument(token.value)),
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_value<T: FromArgument>(name: &str, value: &str) -> Result<T, ParseError> {
+    T::from_argument(value).ok_or_else(|| ParseError::InvalidValue {
+        name: name.to_owned(),
+        value: value.to_owned(),
+        expected: T::EXPECTED,
+        expected_fr: T::EXPECTED_FR,
+    })
+}
+
+impl FromStr for Arguments {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        tokenize(s).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", Ok(vec![]))]
+    #[case("feedback", Ok(vec!["feedback"]))]
+    #[case("  feedback   baz ", Ok(vec!["feedback", "baz"]))]
+    #[case("feedback \"Sprint review\" --anonymous", Ok(vec!["feedback", "Sprint review", "--anonymous"]))]
+    #[case("feedback 'Sprint review'", Ok(vec!["feedback", "Sprint review"]))]
+    #[case("feedback “Sprint review”", Ok(vec!["feedback", "Sprint review"]))]
+    #[case("feedback \"Say \\\"hi\\\"\"", Ok(vec!["feedback", "Say \"hi\""]))]
+    #[case("remind <at>John Doe</at> now", Ok(vec!["remind", "<at>John Doe</at>", "now"]))]
+    #[case("feedback \"Sprint review", Err(ParseError::UnterminatedQuote(9)))]
+    #[case("remind <at>John Doe", Err(ParseError::UnterminatedMention(7)))]
+    fn test_tokenize(#[case] input: &str, #[case] expected: Result<Vec<&str>, ParseError>) {
+        // Act
+        let result = tokenize(input);
+
+        // Assert
+        assert_eq!(
+            expected.map(|x| x.into_iter().map(String::from).collect::<Vec<_>>()),
+            result.map(|x| x.into_iter().map(|x| x.value).collect::<Vec<_>>())
+        );
+    }
+
+    #[test]
+    fn test_tokenize_quoted() {
+        // Act
+        let result = tokenize("feedback \"close\" --anonymous").unwrap();
+
+        // Assert
+        assert_eq!(
+            vec![false, true, false],
+            result.iter().map(|x| x.quoted).collect::<Vec<_>>()
+        );
+    }
+
+    #[rstest]
+    #[case("30s", Some(Duration::from_secs(30)))]
+    #[case("45m", Some(Duration::from_secs(45 * 60)))]
+    #[case("2h", Some(Duration::from_secs(2 * 60 * 60)))]
+    #[case("1h30m", Some(Duration::from_secs(90 * 60)))]
+    #[case("1d", Some(Duration::from_secs(24 * 60 * 60)))]
+    #[case("0m", None)]
+    #[case("2", None)]
+    #[case("h", None)]
+    #[case("2x", None)]
+    #[case("", None)]
+    fn test_duration_from_argument(#[case] input: &str, #[case] expected: Option<Duration>) {
+        // Act
+        let result = Duration::from_argument(input);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[rstest]
+    #[case("<at>John Doe</at>", Some(Mention { name: "John Doe".to_owned() }))]
+    #[case("John Doe", None)]
+    fn test_mention_from_argument(#[case] input: &str, #[case] expected: Option<Mention>) {
+        // Act
+        let result = Mention::from_argument(input);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_arguments() {
+        // Arrange
+        let mut arguments: Arguments = "\"Sprint review\" --close-in 2h --anonymous --count 3"
+            .parse()
+            .unwrap();
+
+        // Act
+        let anonymous = arguments.flag("anonymous");
+        let close_in = arguments.option::<Duration>("close-in");
+        let count = arguments.option::<i64>("count");
+        let title = arguments.positional::<String>("title");
+        let missing = arguments.flag("missing");
+        let finish = arguments.finish();
+
+        // Assert
+        assert!(anonymous);
+        assert_eq!(Ok(Some(Duration::from_secs(2 * 60 * 60))), close_in);
+        assert_eq!(Ok(Some(3)), count);
+        assert_eq!(Ok(Some("Sprint review".to_owned())), title);
+        assert!(!missing);
+        assert_eq!(Ok(()), finish);
+    }
+
+    #[test]
+    fn test_arguments_quoted_flag() {
+        // Arrange
+        let mut arguments: Arguments = "\"--anonymous\"".parse().unwrap();
+
+        // Act
+        let anonymous = arguments.flag("anonymous");
+        let title = arguments.positional::<String>("title");
+
+        // Assert
+        assert!(!anonymous);
+        assert_eq!(Ok(Some("--anonymous".to_owned())), title);
+    }
+
+    #[rstest]
+    #[case("close", true)]
+    #[case("Close --all", true)]
+    #[case("\"Sprint review\" close", false)]
+    #[case("\"Close\"", false)]
+    #[case("'close' --all", false)]
+    #[case("closed", false)]
+    #[case("", false)]
+    fn test_arguments_subcommand(#[case] input: &str, #[case] expected: bool) {
+        // Arrange
+        let mut arguments: Arguments = input.parse().unwrap();
+
+        // Act
+        let result = arguments.subcommand("close");
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[rstest]
+    #[case("--close-in", Err(ParseError::MissingFlagValue("close-in".to_owned())))]
+    #[case("--close-in --anonymous", Err(ParseError::MissingFlagValue("close-in".to_owned())))]
+    #[case("--close-in soon", Err(ParseError::InvalidValue { name: "--close-in".to_owned(), value: "soon".to_owned(), expected: Duration::EXPECTED, expected_fr: Duration::EXPECTED_FR }))]
+    #[case("--close-in 2h", Ok(Some(Duration::from_secs(2 * 60 * 60))))]
+    #[case("", Ok(None))]
+    fn test_arguments_option(
+        #[case] input: &str,
+        #[case] expected: Result<Option<Duration>, ParseError>,
+    ) {
+        // Arrange
+        let mut arguments: Arguments = input.parse().unwrap();
+
+        // Act
+        let result = arguments.option::<Duration>("close-in");
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[rstest]
+    #[case(ParseError::UnknownFlag("foo".to_owned()), Locale::English, "The option `--foo` does not exist for this command.")]
+    #[case(ParseError::UnknownFlag("foo".to_owned()), Locale::French, "L'option `--foo` n'existe pas pour cette commande.")]
+    #[case(ParseError::InvalidValue { name: "--close-in".to_owned(), value: "soon".to_owned(), expected: Duration::EXPECTED, expected_fr: Duration::EXPECTED_FR }, Locale::French, "La valeur `soon` n'est pas valide pour `--close-in`, valeur attendue : une durée comme `30m`, `2h` ou `1h30m`.")]
+    fn test_user_message(
+        #[case] error: ParseError,
+        #[case] locale: Locale,
+        #[case] expected: &str,
+    ) {
+        // Act
+        let result = error.user_message(locale);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[rstest]
+    #[case("", Ok(()))]
+    #[case("--unknown", Err(ParseError::UnknownFlag("unknown".to_owned())))]
+    #[case("extra", Err(ParseError::UnexpectedArgument("extra".to_owned())))]
+    #[case("\"--unknown\"", Err(ParseError::UnexpectedArgument("--unknown".to_owned())))]
+    fn test_arguments_finish(#[case] input: &str, #[case] expected: Result<(), ParseError>) {
+        // Arrange
+        let arguments: Arguments = input.parse().unwrap();
+
+        // Act
+        let result = arguments.finish();
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+}
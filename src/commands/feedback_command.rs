@@ -1,19 +1,42 @@
-use sqlx::{Acquire, PgPool, Postgres, Transaction};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Acquire, PgConnection, PgPool, Postgres, Transaction};
 use tracing::warn;
+use uuid::Uuid;
 
 use crate::{
-    database::queries::{self, feedback_query::FeedbackMetadata},
+    database::queries::{
+        self,
+        feedback_query::{
+            FeedbackAnswer, FeedbackCard, FeedbackEntry, FeedbackMetadata, NewFeedback,
+        },
+    },
     error::{Error, Result},
+    i18n::Locale,
     models::{
-        self,
+        action::CardAction,
         activity::{Activity, Type},
         attachment::ContentType,
-        Attachment, ChannelAccount, ConversationParameters,
+        Action, Attachment, ChannelAccount, ConversationAccount, ConversationParameters,
+        ConversationReference,
     },
-    services::{graph_client::GraphClient, teams_client::TeamsClient},
+    scheduler::{self, Job, JobHandler},
+    services::{graph_client::GraphClient, proactive, teams_client::TeamsClient},
+    state::AppState,
+    utils,
 };
 
-use super::send_adaptive_card;
+use super::{
+    args::{FromArgument, ParseError},
+    feedback_export, feedback_form, feedback_history,
+    feedback_reminder::{self, FeedbackReminder},
+    meeting::{self, FeedbackMeeting, Meeting},
+    router::{ActionHandler, ActionResponse, CommandHandler, Context},
+    send_adaptive_card, send_message, send_typing, Commands, FeedbackArgs,
+};
 
 const EMPTY_STAR: &str = include_str!("../assets/empty_star");
 const HALF_STAR: &str = include_str!("../assets/half_star");
@@ -21,95 +44,715 @@ const FULL_STAR: &str = include_str!("../assets/full_star");
 const FEEDBACK_CARD: &str = include_str!("../assets/feedback_card.json");
 const FEEDBACK_REPORT: &str = include_str!("../assets/feedback_report.json");
 const FALLBACK_NAME: &str = "Unknown";
+/// The number of blocks of the longest bar of the histogram.
+const HISTOGRAM_WIDTH: usize = 10;
+
+pub struct FeedbackCommand;
+
+#[async_trait]
+impl CommandHandler for FeedbackCommand {
+    async fn handle(&self, context: &Context, command: Commands) -> Result<()> {
+        match command {
+            Commands::Feedback(args) => {
+                send_feedback_card(
+                    &context.state.teams_client,
+                    &context.state.graph_client,
+                    &context.state.pool,
+                    &context.activity,
+                    &args,
+                )
+                .await
+            }
+            Commands::FeedbackClose => {
+                close_user_feedbacks(
+                    &context.state.teams_client,
+                    &context.state.pool,
+                    &context.activity,
+                )
+                .await
+            }
+            Commands::FeedbackRemind => {
+                feedback_reminder::send_reminders(
+                    &context.state.teams_client,
+                    &context.state.graph_client,
+                    &context.state.pool,
+                    &context.state.anonymous_secret,
+                    &context.activity,
+                )
+                .await
+            }
+            Commands::FeedbackHistory(args) => {
+                feedback_history::send_history_card(
+                    &context.state.teams_client,
+                    &context.state.pool,
+                    &context.activity,
+                    &args,
+                )
+                .await
+            }
+            Commands::FeedbackExport(args) => {
+                feedback_export::send_export_consent(
+                    &context.state.teams_client,
+                    &context.state.pool,
+                    &context.activity,
+                    &args,
+                )
+                .await
+            }
+            _ => Err(Error::UnknownCommand(command.spec().name.to_owned())),
+        }
+    }
+}
+
+pub struct FeedbackEntryAction;
+
+#[async_trait]
+impl ActionHandler for FeedbackEntryAction {
+    async fn handle(&self, context: &Context, action: CardAction) -> Result<ActionResponse> {
+        let Action::Feedback(ref feedback) = action.action else {
+            return Err(Error::UnknownAction(serde_json::to_value(&action)?));
+        };
+
+        let submission = FeedbackSubmission {
+            rating: feedback.rating,
+            comment: feedback.comment.clone(),
+            answers: Vec::new(),
+        };
+
+        let recorded = handle_feedback_entry(
+            &context.state.teams_client,
+            &context.state.graph_client,
+            &context.state.pool,
+            &context.state.anonymous_secret,
+            &context.activity,
+            &action.instance_id,
+            &submission,
+        )
+        .await?;
+
+        if !recorded {
+            return Ok(get_closed_response(Locale::from(&context.activity)));
+        }
+
+        get_user_feedback_card(
+            &context.state.pool,
+            &context.state.anonymous_secret,
+            &action.instance_id,
+            &context.activity.from.id,
+        )
+        .await
+    }
+}
+
+/// The message shown to a user answering a closed feedback.
+pub(super) fn get_closed_response(locale: Locale) -> ActionResponse {
+    let message = match locale {
+        Locale::French => "Ce feedback est clos, votre réponse n'a pas été enregistrée.",
+        Locale::English => "This feedback is closed, your answer was not saved.",
+    };
+
+    ActionResponse::Message(message.to_owned())
+}
+
+pub struct FeedbackRefreshAction;
+
+#[async_trait]
+impl ActionHandler for FeedbackRefreshAction {
+    async fn handle(&self, context: &Context, action: CardAction) -> Result<ActionResponse> {
+        get_user_feedback_card(
+            &context.state.pool,
+            &context.state.anonymous_secret,
+            &action.instance_id,
+            &context.activity.from.id,
+        )
+        .await
+    }
+}
 
 pub async fn send_feedback_card(
     teams_client: &TeamsClient,
     graph_client: &GraphClient,
     pool: &PgPool,
     activity: &Activity,
+    args: &FeedbackArgs,
 ) -> Result<()> {
     let name = activity.from.name.as_deref().unwrap_or(FALLBACK_NAME);
+    let locale = Locale::from(activity);
 
-    let response = send_adaptive_card(
-        teams_client,
-        activity,
-        &serde_json::from_str(&FEEDBACK_CARD.replace("{name}", name))?,
-    )
-    .await?;
+    let instance_id = Uuid::new_v4().to_string();
+    let closes_at = args
+        .close_in
+        .map(|x| get_deadline("--close-in", x))
+        .transpose()?;
+    let remind_at = args
+        .remind_in
+        .map(|x| get_deadline("--remind-in", x))
+        .transpose()?;
 
-    let user_id = &activity.from.id;
-    let chat = graph_client.get_chat(&activity.conversation.id).await;
-    let chat_name = match chat {
-        Ok(ref chat) => &chat.topic,
+    let questions = args
+        .template
+        .map(|x| feedback_form::get_template_questions(x, locale))
+        .unwrap_or_default();
+
+    let chat = match graph_client.get_chat(&activity.conversation.id).await {
+        Ok(chat) => Some(chat),
         Err(e) => {
-            warn!("An error occured while fetching the chat name : {:?}", e);
-            FALLBACK_NAME
+            warn!("An error occured while fetching the chat : {:?}", e);
+            None
+        }
+    };
+    let meeting = match chat.as_ref().and_then(|x| x.online_meeting_info.as_ref()) {
+        Some(info) => {
+            send_typing(teams_client, activity).await;
+            meeting::get_meeting(graph_client, info)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("An error occured while fetching the meeting : {:?}", e);
+                    None
+                })
         }
+        None => None,
+    };
+
+    let title = args
+        .title
+        .clone()
+        .or_else(|| meeting.as_ref().and_then(Meeting::get_title));
+    let organizer_name = meeting.as_ref().and_then(|x| x.organizer_name.as_deref());
+
+    let content = FeedbackCardContent {
+        instance_id: &instance_id,
+        title: title.as_deref(),
+        owner_name: organizer_name.unwrap_or(name),
+        anonymous: args.anonymous,
+        closes_at: closes_at.as_ref(),
+        closed: false,
+        locale,
+    };
+    let card = match args.template {
+        Some(_) => feedback_form::get_feedback_form_adaptive_card(&content, &questions, &[]),
+        None => get_feedback_adaptive_card(&content, None)?,
     };
 
+    let response = send_adaptive_card(teams_client, activity, &card).await?;
+
+    let user_id = &activity.from.id;
+    let chat_name = args
+        .title
+        .clone()
+        .or_else(|| meeting.as_ref().and_then(|x| x.subject.clone()))
+        .or_else(|| chat.and_then(|x| x.topic))
+        .unwrap_or_else(|| FALLBACK_NAME.to_owned());
+
     let mut conn = pool.acquire().await?;
     let mut tx = conn.begin().await?;
 
     queries::user_query::create_user(user_id, name, &mut *tx).await?;
-    queries::feedback_query::create_feedback(user_id, &response.id, &chat_name, &mut *tx).await?;
+    queries::feedback_query::create_feedback(
+        &NewFeedback {
+            owner_id: user_id,
+            card_id: &response.id,
+            instance_id: &instance_id,
+            title: title.as_deref(),
+            conversation_name: &chat_name,
+            anonymous: args.anonymous,
+            conversation_id: &activity.conversation.id,
+            closes_at,
+            template: args.template.map(|x| x.name),
+            meeting_id: meeting.as_ref().and_then(|x| x.id.as_deref()),
+            organizer_id: meeting.as_ref().map(|x| x.organizer_id.as_str()),
+            organizer_name,
+            locale: locale.as_str(),
+        },
+        &mut *tx,
+    )
+    .await?;
+
+    queries::conversation_reference_query::save_reference(
+        &ConversationReference {
+            activity_id: Some(response.id.clone()),
+            ..activity.get_conversation_reference()
+        },
+        &mut *tx,
+    )
+    .await?;
+
+    for question in &questions {
+        queries::feedback_query::add_question(&response.id, question, &mut *tx).await?;
+    }
+
+    if let Some(closes_at) = closes_at {
+        scheduler::enqueue(
+            &FeedbackDeadline {
+                instance_id: instance_id.clone(),
+            },
+            closes_at,
+            &mut *tx,
+        )
+        .await?;
+    }
+
+    if let Some(remind_at) = remind_at {
+        scheduler::enqueue(
+            &FeedbackReminder {
+                instance_id: instance_id.clone(),
+            },
+            remind_at,
+            &mut *tx,
+        )
+        .await?;
+    }
 
     tx.commit().await?;
 
     Ok(())
 }
 
-pub async fn handle_feedback_entry(
+/// The date after the given delay, `option` naming the argument the delay comes from.
+fn get_deadline(option: &str, delay: Duration) -> Result<DateTime<Utc>> {
+    chrono::Duration::from_std(delay)
+        .ok()
+        .and_then(|x| Utc::now().checked_add_signed(x))
+        .ok_or_else(|| {
+            Error::Arguments(ParseError::InvalidValue {
+                name: option.to_owned(),
+                value: format!("{}s", delay.as_secs()),
+                expected: Duration::EXPECTED,
+                expected_fr: Duration::EXPECTED_FR,
+            })
+        })
+}
+
+/// Closes the open feedbacks of the user in the conversation the command was sent from.
+async fn close_user_feedbacks(
     client: &TeamsClient,
     pool: &PgPool,
     activity: &Activity,
-    feedback: &models::action::Feedback,
 ) -> Result<()> {
-    let card_id = activity
-        .reply_to_id
-        .as_ref()
-        .ok_or(Error::MissingValue("reply_to_id"))?;
+    let instance_ids = queries::feedback_query::close_feedbacks(
+        &activity.from.id,
+        &activity.conversation.id,
+        pool,
+    )
+    .await?;
 
-    let user_id = &activity.from.id;
+    update_closed_cards(client, pool, &instance_ids).await;
 
-    let (base_url, mut response) = activity.create_response();
+    let message = match (instance_ids.is_empty(), Locale::from(activity)) {
+        (true, Locale::French) => {
+            "Vous n'avez aucune demande de feedback ouverte dans cette conversation."
+        }
+        (true, Locale::English) => "You have no open feedback request in this conversation.",
+        (false, Locale::French) => "Vos demandes de feedback sont clôturées.",
+        (false, Locale::English) => "Your feedback requests are closed.",
+    };
+
+    send_message(client, activity, message).await
+}
+
+/// Closes a feedback once its deadline is over, enqueued with the feedback.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackDeadline {
+    pub instance_id: String,
+}
+
+impl Job for FeedbackDeadline {
+    const KIND: &'static str = "feedbackDeadline";
+}
+
+pub struct FeedbackDeadlineJob;
+
+#[async_trait]
+impl JobHandler for FeedbackDeadlineJob {
+    async fn run(&self, state: &AppState, payload: serde_json::Value) -> Result<()> {
+        let FeedbackDeadline { instance_id } = serde_json::from_value(payload)?;
 
+        queries::feedback_query::close_expired_feedback(&instance_id, &state.pool).await?;
+
+        // The card is replaced even if the owner closed the feedback first, so a retried job still updates it
+        update_closed_card(&state.teams_client, &state.pool, &instance_id).await
+    }
+}
+
+/// Replaces the cards of the closed feedbacks, so nobody can answer them anymore. The feedbacks are already closed, so a card failing to update is only logged.
+async fn update_closed_cards(client: &TeamsClient, pool: &PgPool, instance_ids: &[String]) {
+    for instance_id in instance_ids {
+        if let Err(e) = update_closed_card(client, pool, instance_id).await {
+            warn!(
+                "An error occured while updating the closed feedback {instance_id} : {:?}",
+                e
+            );
+        }
+    }
+}
+
+async fn update_closed_card(client: &TeamsClient, pool: &PgPool, instance_id: &str) -> Result<()> {
     let mut conn = pool.acquire().await?;
-    let mut tx = conn.begin().await?;
+
+    let Some(card) =
+        queries::feedback_query::get_feedback_card_by_instance_id(instance_id, &mut *conn).await?
+    else {
+        return Ok(());
+    };
+
+    let Some(reference) =
+        queries::conversation_reference_query::get_reference(&card.id, &mut *conn).await?
+    else {
+        warn!("The conversation of the feedback {instance_id} is unknown, its card is not updated");
+        return Ok(());
+    };
+
+    let content = render_feedback_card(instance_id, &card, None, &mut conn).await?;
+
+    let activity = Activity {
+        r#type: Type::Message,
+        attachments: Some(vec![Attachment {
+            content: Some(content),
+            content_type: Some(ContentType::Adaptive),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    };
+
+    proactive::update_reference(client, &reference, &activity).await?;
+
+    Ok(())
+}
+
+/// Renders the feedback card as seen by the given user, with their current rating and comment.
+pub(super) async fn get_user_feedback_card(
+    pool: &PgPool,
+    secret: &str,
+    instance_id: &str,
+    user_id: &str,
+) -> Result<ActionResponse> {
+    let mut conn = pool.acquire().await?;
+
+    // The card is sent before the feedback is saved, so it may be refreshed before it exists.
+    let Some(feedback) =
+        queries::feedback_query::get_feedback_card_by_instance_id(instance_id, &mut *conn).await?
+    else {
+        return Ok(ActionResponse::Empty);
+    };
+
+    let respondent_key = utils::respondent_key(secret, &feedback.id, user_id, feedback.anonymous);
+    let card =
+        render_feedback_card(instance_id, &feedback, Some(&respondent_key), &mut conn).await?;
+
+    Ok(ActionResponse::Card(card))
+}
+
+/// Renders the card of a feedback, either the rating or the form of its template, with the answers of the respondent if any.
+async fn render_feedback_card(
+    instance_id: &str,
+    feedback: &FeedbackCard,
+    respondent_key: Option<&str>,
+    conn: &mut PgConnection,
+) -> Result<serde_json::Value> {
+    let content = FeedbackCardContent::new(instance_id, feedback);
+
+    if feedback.template.is_some() {
+        let questions = queries::feedback_query::get_questions(&feedback.id, &mut *conn).await?;
+        let answers = match respondent_key {
+            Some(respondent_key) => {
+                queries::feedback_query::get_answers(&feedback.id, Some(respondent_key), &mut *conn)
+                    .await?
+            }
+            None => Vec::new(),
+        };
+
+        return Ok(feedback_form::get_feedback_form_adaptive_card(
+            &content, &questions, &answers,
+        ));
+    }
+
+    let entry = match respondent_key {
+        Some(respondent_key) => {
+            queries::feedback_query::get_feedback_entry(&feedback.id, respondent_key, &mut *conn)
+                .await?
+        }
+        None => None,
+    };
+
+    get_feedback_adaptive_card(&content, entry.as_ref())
+}
+
+/// What the feedback card shows, whoever looks at it.
+pub(super) struct FeedbackCardContent<'a> {
+    pub instance_id: &'a str,
+    pub title: Option<&'a str>,
+    pub owner_name: &'a str,
+    pub anonymous: bool,
+    pub closes_at: Option<&'a DateTime<Utc>>,
+    pub closed: bool,
+    /// The language of the owner, in which the card is written for every respondent.
+    pub locale: Locale,
+}
+
+impl<'a> FeedbackCardContent<'a> {
+    fn new(instance_id: &'a str, card: &'a FeedbackCard) -> Self {
+        Self {
+            instance_id,
+            title: card.title.as_deref(),
+            owner_name: card.owner_name.as_deref().unwrap_or(FALLBACK_NAME),
+            anonymous: card.anonymous,
+            closes_at: card.closes_at.as_ref(),
+            closed: card.closed,
+            locale: Locale::parse(&card.locale),
+        }
+    }
+}
+
+pub(super) fn get_fallback_title(locale: Locale) -> &'static str {
+    match locale {
+        Locale::French => "Demande de feedback",
+        Locale::English => "Feedback request",
+    }
+}
+
+pub(super) fn get_closed_text(locale: Locale) -> &'static str {
+    match locale {
+        Locale::French => "🔒 Ce feedback est clos, les réponses ne sont plus acceptées.",
+        Locale::English => "🔒 This feedback is closed, answers are no longer accepted.",
+    }
+}
+
+pub(super) fn get_subtitle(owner_name: &str, anonymous: bool, locale: Locale) -> String {
+    match (anonymous, locale) {
+        (true, Locale::French) => format!(
+            "Par {owner_name} · 🔒 Feedback anonyme : vos réponses ne sont pas liées à votre nom"
+        ),
+        (true, Locale::English) => format!(
+            "By {owner_name} · 🔒 Anonymous feedback: your answers are not linked to your name"
+        ),
+        (false, Locale::French) => format!("Par {owner_name}"),
+        (false, Locale::English) => format!("By {owner_name}"),
+    }
+}
+
+/// Renders a date in the time zone of the user, with the date functions of the Adaptive Cards.
+pub(super) fn get_date_time_text(date: &DateTime<Utc>, locale: Locale) -> String {
+    let date = date.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    match locale {
+        Locale::French => format!("{{{{DATE({date}, SHORT)}}}} à {{{{TIME({date})}}}}"),
+        Locale::English => format!("{{{{DATE({date}, SHORT)}}}} at {{{{TIME({date})}}}}"),
+    }
+}
+
+pub(super) fn get_deadline_text(closes_at: &DateTime<Utc>, locale: Locale) -> String {
+    let date = get_date_time_text(closes_at, locale);
+
+    match locale {
+        Locale::French => format!("Clôture le {date}"),
+        Locale::English => format!("Closes on {date}"),
+    }
+}
+
+fn get_feedback_adaptive_card(
+    content: &FeedbackCardContent,
+    entry: Option<&FeedbackEntry>,
+) -> Result<serde_json::Value> {
+    let FeedbackCardContent {
+        instance_id,
+        title,
+        owner_name,
+        anonymous,
+        closes_at,
+        closed,
+        locale,
+    } = *content;
+
+    let mut card: serde_json::Value =
+        serde_json::from_str(&FEEDBACK_CARD.replace("{instance_id}", instance_id))?;
+
+    card["body"][0]["text"] = serde_json::json!(title.unwrap_or(get_fallback_title(locale)));
+    card["body"][1]["text"] = serde_json::json!(get_subtitle(owner_name, anonymous, locale));
+    card["body"][2]["placeholder"] = serde_json::json!(match locale {
+        Locale::French => "Ajoutez un commentaire ici ...",
+        Locale::English => "Add a comment here ...",
+    });
+
+    if let Some(entry) = entry {
+        card["body"][2]["value"] = serde_json::json!(entry.comment);
+
+        if let Some(stars) = card["body"][3]["columns"].as_array_mut() {
+            for (i, star) in stars.iter_mut().enumerate() {
+                star["items"][0]["url"] = match (i as i32) < entry.rating {
+                    true => serde_json::json!(FULL_STAR),
+                    false => serde_json::json!(EMPTY_STAR),
+                };
+            }
+        }
+
+        if let Some(body) = card["body"].as_array_mut() {
+            body.push(serde_json::json!({
+                "type": "TextBlock",
+                "text": match locale {
+                    Locale::French => format!("Votre note : {}/5", entry.rating),
+                    Locale::English => format!("Your rating: {}/5", entry.rating),
+                },
+                "wrap": true,
+                "isSubtle": true,
+                "horizontalAlignment": "Center"
+            }));
+        }
+    }
+
+    if closed {
+        card["body"][2] = serde_json::json!({
+            "type": "TextBlock",
+            "text": get_closed_text(locale),
+            "wrap": true,
+            "separator": true,
+            "spacing": "extraLarge"
+        });
+
+        if let Some(stars) = card["body"][3]["columns"].as_array_mut() {
+            for star in stars {
+                if let Some(image) = star["items"][0].as_object_mut() {
+                    image.remove("selectAction");
+                }
+            }
+        }
+    } else if let Some(closes_at) = closes_at {
+        if let Some(body) = card["body"].as_array_mut() {
+            body.push(serde_json::json!({
+                "type": "TextBlock",
+                "text": get_deadline_text(closes_at, locale),
+                "wrap": true,
+                "isSubtle": true,
+                "horizontalAlignment": "Center"
+            }));
+        }
+    }
+
+    Ok(card)
+}
+
+/// The answer of a user to a feedback.
+pub struct FeedbackSubmission {
+    /// The overall rating, from 1 to 5.
+    pub rating: i32,
+    pub comment: Option<String>,
+    /// The answers to the questions of the template, if any.
+    pub answers: Vec<FeedbackAnswer>,
+}
+
+/// Saves the answer of the user and updates the report of the owner, the response rate being computed over the attendees when the feedback was requested in a meeting. Returns `false` when the feedback is closed, the answer being rejected.
+pub async fn handle_feedback_entry(
+    client: &TeamsClient,
+    graph_client: &GraphClient,
+    pool: &PgPool,
+    secret: &str,
+    activity: &Activity,
+    instance_id: &str,
+    submission: &FeedbackSubmission,
+) -> Result<bool> {
+    let user_id = &activity.from.id;
+
+    let (base_url, mut response) = activity.create_response();
 
     let FeedbackMetadata {
+        id: card_id,
+        anonymous,
+        closed,
         conversation_id,
         owner_id,
         report_id,
-    } = queries::feedback_query::get_feedback_by_id(card_id, &mut *tx).await?;
+        meeting_id,
+        organizer_id,
+        attendance_report_id,
+        attendees_count,
+        attendance_checked_at,
+        created_at,
+        locale,
+    } = queries::feedback_query::get_feedback_by_instance_id(instance_id, pool)
+        .await?
+        .ok_or_else(|| Error::UnknownAction(serde_json::json!({ "instanceId": instance_id })))?;
 
-    let conversation_id = get_or_create_conversation(
-        client,
-        base_url,
-        conversation_id,
-        activity,
-        &owner_id,
-        &mut tx,
-    )
-    .await?;
+    if closed {
+        return Ok(false);
+    }
+
+    // Counted before the transaction, so no connection is held while Graph and Teams are called
+    let members_count = match attendees_count {
+        Some(attendees_count) => usize::try_from(attendees_count).ok(),
+        None => {
+            let attendee_ids = meeting::get_attendee_ids(
+                graph_client,
+                pool,
+                &FeedbackMeeting {
+                    feedback_id: &card_id,
+                    meeting_id: meeting_id.as_deref(),
+                    organizer_id: organizer_id.as_deref(),
+                    created_at,
+                    attendance_report_id: attendance_report_id.as_deref(),
+                    attendance_checked_at,
+                },
+            )
+            .await;
+
+            match attendee_ids {
+                Some(attendee_ids) => Some(attendee_ids.len()),
+                None => get_members_count(client, activity).await,
+            }
+        }
+    };
+
+    let mut conn = pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    // Checked again once locked, the feedback may have been closed meanwhile
+    if !queries::feedback_query::lock_open_feedback(&card_id, &mut *tx).await? {
+        return Ok(false);
+    }
+
+    let conversation_id =
+        get_or_create_conversation(client, conversation_id, activity, &owner_id, &mut tx).await?;
+
+    let respondent_key = utils::respondent_key(secret, &card_id, user_id, anonymous);
+
+    if !anonymous {
+        if let Some(ref name) = activity.from.name {
+            queries::user_query::create_user(user_id, name, &mut *tx).await?;
+        }
+    }
 
     queries::feedback_query::create_or_update_feedback_entry(
-        card_id,
-        user_id,
-        feedback.rating,
-        feedback.comment.as_deref(),
+        &card_id,
+        &respondent_key,
+        (!anonymous).then_some(user_id.as_str()),
+        submission.rating,
+        submission.comment.as_deref(),
         &mut *tx,
     )
     .await?;
 
-    let feedbacks = queries::feedback_query::get_feedbacks_by_id(card_id, &mut *tx).await?;
+    queries::feedback_query::set_answers(&card_id, &respondent_key, &submission.answers, &mut tx)
+        .await?;
 
-    let content = get_feedback_report_adaptive_card(&feedbacks)?;
+    let feedbacks = queries::feedback_query::get_feedbacks_by_id(&card_id, &mut *tx).await?;
+    let questions = queries::feedback_query::get_questions(&card_id, &mut *tx).await?;
+    let answers = queries::feedback_query::get_answers(&card_id, None, &mut *tx).await?;
+
+    // The report is read by the owner, in the language of the feedback
+    let locale = Locale::parse(&locale);
+    let mut content =
+        get_feedback_report_adaptive_card(&feedbacks, anonymous, members_count, locale)?;
+    if let Some(body) = content["body"].as_array_mut() {
+        body.extend(feedback_form::get_questions_report(
+            &questions, &answers, locale,
+        ));
+    }
 
     response.recipient = ChannelAccount::default();
     response.r#type = Type::Message;
     response.attachments = Some(vec![Attachment {
         content: Some(content),
         content_type: Some(ContentType::Adaptive),
+        ..Default::default()
     }]);
 
     match report_id {
@@ -123,17 +766,133 @@ pub async fn handle_feedback_entry(
                 .send_to_conversation(base_url, &conversation_id, &response)
                 .await?;
 
-            queries::feedback_query::add_report(card_id, &response.id, &mut *tx).await?;
+            queries::feedback_query::add_report(&card_id, &response.id, &mut *tx).await?;
+            queries::conversation_reference_query::save_reference(
+                &ConversationReference {
+                    activity_id: Some(response.id),
+                    conversation: ConversationAccount {
+                        id: conversation_id,
+                        conversation_type: "personal".to_owned(),
+                        tenant_id: activity.conversation.tenant_id.clone(),
+                        ..Default::default()
+                    },
+                    ..activity.get_conversation_reference()
+                },
+                &mut *tx,
+            )
+            .await?;
         }
     }
 
     tx.commit().await?;
 
-    Ok(())
+    Ok(true)
+}
+
+/// Counts the users who can answer the feedback, the members of its conversation but the bot. The response rate is only informative, so a failure is logged and ignored.
+async fn get_members_count(client: &TeamsClient, activity: &Activity) -> Option<usize> {
+    match client
+        .get_conversation_members(activity.service_url.as_deref(), &activity.conversation.id)
+        .await
+    {
+        Ok(members) => Some(
+            members
+                .iter()
+                .filter(|x| x.id != activity.recipient.id)
+                .count(),
+        ),
+        Err(e) => {
+            warn!(
+                "An error occured while fetching the members of the conversation : {:?}",
+                e
+            );
+            None
+        }
+    }
+}
+
+/// The distribution of the ratings of a feedback.
+#[derive(Debug, PartialEq)]
+struct RatingStatistics {
+    /// The number of ratings of each value, from 1 to 5 stars.
+    counts: [usize; 5],
+    median: f32,
+}
+
+fn get_rating_statistics(ratings: &[i64]) -> RatingStatistics {
+    let mut counts = [0; 5];
+    for rating in ratings.iter().filter(|x| (1..=5).contains(*x)) {
+        counts[*rating as usize - 1] += 1;
+    }
+
+    let mut sorted = ratings.to_vec();
+    sorted.sort_unstable();
+    let median = match sorted.len() {
+        0 => 0.0,
+        len if len % 2 == 0 => (sorted[len / 2 - 1] + sorted[len / 2]) as f32 / 2.0,
+        len => sorted[len / 2] as f32,
+    };
+
+    RatingStatistics { counts, median }
+}
+
+/// Renders the number of ratings of each value as horizontal bars, the 5 stars first.
+fn get_histogram(counts: &[usize; 5]) -> Vec<serde_json::Value> {
+    let max = counts.iter().copied().max().unwrap_or_default().max(1);
+
+    (1..=5)
+        .rev()
+        .zip(counts.iter().rev())
+        .map(|(stars, count)| {
+            serde_json::json!({
+                "type": "ColumnSet",
+                "spacing": "Small",
+                "columns": [
+                    {
+                        "type": "Column",
+                        "width": "auto",
+                        "items": [{ "type": "TextBlock", "text": format!("{stars} ★") }]
+                    },
+                    {
+                        "type": "Column",
+                        "width": "stretch",
+                        "items": [{
+                            "type": "TextBlock",
+                            "text": "█".repeat(count * HISTOGRAM_WIDTH / max),
+                            "color": "Accent"
+                        }]
+                    },
+                    {
+                        "type": "Column",
+                        "width": "auto",
+                        "items": [{ "type": "TextBlock", "text": count.to_string() }]
+                    }
+                ]
+            })
+        })
+        .collect()
+}
+
+/// The share of the members who answered. A respondent may not be counted as a member, e.g. when only the attendees of a meeting are, so the rate never exceeds 100 %.
+fn get_response_rate_text(feedbacks_count: usize, members_count: usize, locale: Locale) -> String {
+    let respondents_count = feedbacks_count.min(members_count);
+    let rate = respondents_count as f32 * 100.0 / members_count as f32;
+
+    match locale {
+        Locale::French => {
+            format!("Taux de réponse : {respondents_count}/{members_count} ({rate:.0} %)")
+        }
+        Locale::English => {
+            format!("Response rate: {respondents_count}/{members_count} ({rate:.0}%)")
+        }
+    }
 }
 
 fn get_feedback_report_adaptive_card(
     feedbacks: &[queries::feedback_query::Feedback],
+    anonymous: bool,
+    members_count: Option<usize>,
+    locale: Locale,
 ) -> Result<serde_json::Value> {
     let comments: Vec<_> = feedbacks
         .iter()
@@ -190,15 +949,103 @@ fn get_feedback_report_adaptive_card(
             .replace("{feedbacks_count}", &feedbacks_count.to_string()),
     )?;
 
+    let (heading, show_comments, hide_comments, average_heading) = match locale {
+        Locale::French => (
+            "Rapport de feedback",
+            format!("Afficher les commentaires ({comments_count})"),
+            "Masquer les commentaires",
+            format!("Moyenne ({feedbacks_count})"),
+        ),
+        Locale::English => (
+            "Feedback report",
+            format!("Show the comments ({comments_count})"),
+            "Hide the comments",
+            format!("Average ({feedbacks_count})"),
+        ),
+    };
+    feedback_report["body"][0]["text"] = serde_json::json!(heading);
+    feedback_report["body"][2]["columns"][0]["items"][0]["text"] = serde_json::json!(show_comments);
+    feedback_report["body"][2]["columns"][0]["items"][1]["text"] = serde_json::json!(hide_comments);
+    feedback_report["body"][4]["text"] = serde_json::json!(average_heading);
+
+    if anonymous {
+        feedback_report["body"][1]["text"] = serde_json::json!(match locale {
+            Locale::French => format!("{name} · Anonyme"),
+            Locale::English => format!("{name} · Anonymous"),
+        });
+    }
+
     feedback_report["body"][3]["items"] = serde_json::Value::Array(comments);
     feedback_report["body"][5]["columns"] = serde_json::Value::Array(stars);
 
+    let ratings: Vec<_> = feedbacks.iter().map(|x| x.rating).collect();
+    let statistics = get_rating_statistics(&ratings);
+
+    if let Some(body) = feedback_report["body"].as_array_mut() {
+        body.push(serde_json::json!({
+            "type": "TextBlock",
+            "text": match locale {
+                Locale::French => format!("Médiane : {}/5", statistics.median),
+                Locale::English => format!("Median: {}/5", statistics.median),
+            },
+            "wrap": true,
+            "horizontalAlignment": "Center"
+        }));
+
+        if let Some(members_count) = members_count.filter(|x| *x > 0) {
+            body.push(serde_json::json!({
+                "type": "TextBlock",
+                "text": get_response_rate_text(feedbacks_count, members_count, locale),
+                "wrap": true,
+                "horizontalAlignment": "Center"
+            }));
+        }
+
+        body.push(serde_json::json!({
+            "type": "TextBlock",
+            "text": match locale {
+                Locale::French => "Répartition",
+                Locale::English => "Distribution",
+            },
+            "wrap": true,
+            "style": "heading",
+            "separator": true,
+            "spacing": "ExtraLarge"
+        }));
+        body.extend(get_histogram(&statistics.counts));
+
+        let requested_at = &feedbacks[0].requested_at;
+        let answered_at = feedbacks.iter().map(|x| &x.answered_at).max();
+        let (requested_text, answered_text) = match locale {
+            Locale::French => ("Demandé le", "Dernière réponse le"),
+            Locale::English => ("Requested on", "Last answer on"),
+        };
+        let mut dates = format!(
+            "{requested_text} {}",
+            get_date_time_text(requested_at, locale)
+        );
+        if let Some(answered_at) = answered_at {
+            dates.push_str(&format!(
+                " · {answered_text} {}",
+                get_date_time_text(answered_at, locale)
+            ));
+        }
+        body.push(serde_json::json!({
+            "type": "TextBlock",
+            "text": dates,
+            "wrap": true,
+            "isSubtle": true,
+            "size": "Small",
+            "separator": true,
+            "spacing": "ExtraLarge"
+        }));
+    }
+
     Ok(feedback_report)
 }
 
-async fn get_or_create_conversation(
+pub(super) async fn get_or_create_conversation(
     client: &TeamsClient,
-    base_url: Option<&str>,
     conversation_id: Option<String>,
     activity: &Activity,
     user_id: &str,
@@ -207,7 +1054,9 @@ async fn get_or_create_conversation(
     match conversation_id {
         Some(conversation_id) => Ok(conversation_id),
         None => {
-            let conversation_id = create_conversation(client, base_url, activity, user_id).await?;
+            let conversation_id =
+                create_conversation(client, &activity.get_conversation_reference(), user_id)
+                    .await?;
             queries::user_query::update_conversation(user_id, &conversation_id, &mut **tx).await?;
 
             Ok(conversation_id)
@@ -215,25 +1064,329 @@ async fn get_or_create_conversation(
     }
 }
 
-async fn create_conversation(
+/// Creates the personal chat of the bot with the user, in the tenant of the reference. An existing chat is returned as is by Teams.
+pub(super) async fn create_conversation(
     client: &TeamsClient,
-    base_url: Option<&str>,
-    activity: &Activity,
+    reference: &ConversationReference,
     user_id: &str,
 ) -> Result<String> {
     let conversation_response = client
         .create_conversation(
-            base_url,
+            reference.service_url.as_deref(),
             &ConversationParameters {
-                bot: activity.recipient.clone(),
+                bot: reference.bot.clone().unwrap_or_default(),
                 members: Some(vec![ChannelAccount {
                     id: user_id.to_owned(),
                     ..Default::default()
                 }]),
-                tenant_id: activity.conversation.tenant_id.clone(),
+                tenant_id: reference.conversation.tenant_id.clone(),
             },
         )
         .await?;
 
     Ok(conversation_response.id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    fn content(title: Option<&str>) -> FeedbackCardContent<'_> {
+        FeedbackCardContent {
+            instance_id: "42",
+            title,
+            owner_name: "John",
+            anonymous: false,
+            closes_at: None,
+            closed: false,
+            locale: Locale::French,
+        }
+    }
+
+    #[test]
+    fn test_get_feedback_adaptive_card() {
+        // Act
+        let result = get_feedback_adaptive_card(&content(Some("Sprint review")), None).unwrap();
+
+        // Assert
+        assert_eq!("Sprint review", result["body"][0]["text"]);
+        assert_eq!("Par John", result["body"][1]["text"]);
+        assert_eq!("42", result["refresh"]["action"]["data"]["instanceId"]);
+        for star in result["body"][3]["columns"].as_array().unwrap() {
+            let action = &star["items"][0]["selectAction"];
+            assert_eq!("Action.Execute", action["type"]);
+            assert_eq!(Action::FEEDBACK, action["verb"]);
+            assert_eq!("42", action["data"]["instanceId"]);
+        }
+    }
+
+    #[test]
+    fn test_get_feedback_adaptive_card_in_english() {
+        // Arrange
+        let closes_at = Utc.with_ymd_and_hms(2026, 10, 18, 14, 30, 0).unwrap();
+        let entry = FeedbackEntry {
+            rating: 4,
+            comment: None,
+        };
+
+        // Act
+        let result = get_feedback_adaptive_card(
+            &FeedbackCardContent {
+                closes_at: Some(&closes_at),
+                locale: Locale::English,
+                ..content(None)
+            },
+            Some(&entry),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!("Feedback request", result["body"][0]["text"]);
+        assert_eq!("By John", result["body"][1]["text"]);
+        assert_eq!("Add a comment here ...", result["body"][2]["placeholder"]);
+        assert_eq!("Your rating: 4/5", result["body"][4]["text"]);
+        assert_eq!(
+            "Closes on {{DATE(2026-10-18T14:30:00Z, SHORT)}} at {{TIME(2026-10-18T14:30:00Z)}}",
+            result["body"][5]["text"]
+        );
+    }
+
+    #[test]
+    fn test_get_feedback_adaptive_card_anonymous() {
+        // Act
+        let result = get_feedback_adaptive_card(
+            &FeedbackCardContent {
+                anonymous: true,
+                ..content(None)
+            },
+            None,
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!(
+            "Par John · 🔒 Feedback anonyme : vos réponses ne sont pas liées à votre nom",
+            result["body"][1]["text"]
+        );
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(3)]
+    #[case(5)]
+    fn test_get_feedback_adaptive_card_with_entry(#[case] rating: i32) {
+        // Arrange
+        let entry = FeedbackEntry {
+            rating,
+            comment: Some("Top".to_owned()),
+        };
+
+        // Act
+        let result = get_feedback_adaptive_card(&content(None), Some(&entry)).unwrap();
+
+        // Assert
+        assert_eq!("Top", result["body"][2]["value"]);
+        let stars = result["body"][3]["columns"].as_array().unwrap();
+        let full = stars
+            .iter()
+            .filter(|star| star["items"][0]["url"] == FULL_STAR)
+            .count();
+        assert_eq!(rating as usize, full);
+        assert_eq!(
+            format!("Votre note : {rating}/5"),
+            result["body"][4]["text"]
+        );
+    }
+
+    #[test]
+    fn test_get_feedback_adaptive_card_with_deadline() {
+        // Arrange
+        let closes_at = DateTime::parse_from_rfc3339("2026-10-18T14:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        // Act
+        let result = get_feedback_adaptive_card(
+            &FeedbackCardContent {
+                closes_at: Some(&closes_at),
+                ..content(None)
+            },
+            None,
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!("Input.Text", result["body"][2]["type"]);
+        assert_eq!(
+            "Clôture le {{DATE(2026-10-18T14:30:00Z, SHORT)}} à {{TIME(2026-10-18T14:30:00Z)}}",
+            result["body"][4]["text"]
+        );
+    }
+
+    #[test]
+    fn test_get_feedback_adaptive_card_closed() {
+        // Arrange
+        let entry = FeedbackEntry {
+            rating: 4,
+            comment: None,
+        };
+
+        // Act
+        let result = get_feedback_adaptive_card(
+            &FeedbackCardContent {
+                closed: true,
+                ..content(None)
+            },
+            Some(&entry),
+        )
+        .unwrap();
+
+        // Assert
+        assert_eq!("TextBlock", result["body"][2]["type"]);
+        assert_eq!("Votre note : 4/5", result["body"][4]["text"]);
+        for star in result["body"][3]["columns"].as_array().unwrap() {
+            assert!(star["items"][0].get("selectAction").is_none());
+        }
+    }
+
+    #[rstest]
+    #[case(&[], [0, 0, 0, 0, 0], 0.0)]
+    #[case(&[4], [0, 0, 0, 1, 0], 4.0)]
+    #[case(&[1, 5, 5, 1], [2, 0, 0, 0, 2], 3.0)]
+    #[case(&[5, 3, 4, 4], [0, 0, 1, 2, 1], 4.0)]
+    #[case(&[2, 5, 3], [0, 1, 1, 0, 1], 3.0)]
+    #[case(&[1, 2], [1, 1, 0, 0, 0], 1.5)]
+    fn test_get_rating_statistics(
+        #[case] ratings: &[i64],
+        #[case] counts: [usize; 5],
+        #[case] median: f32,
+    ) {
+        // Act
+        let result = get_rating_statistics(ratings);
+
+        // Assert
+        assert_eq!(RatingStatistics { counts, median }, result);
+    }
+
+    #[test]
+    fn test_get_histogram() {
+        // Act
+        let result = get_histogram(&[2, 0, 0, 1, 4]);
+
+        // Assert
+        let bars: Vec<_> = result
+            .iter()
+            .map(|x| {
+                (
+                    x["columns"][0]["items"][0]["text"].as_str().unwrap(),
+                    x["columns"][1]["items"][0]["text"]
+                        .as_str()
+                        .unwrap()
+                        .chars()
+                        .count(),
+                    x["columns"][2]["items"][0]["text"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("5 ★", 10, "4"),
+                ("4 ★", 2, "1"),
+                ("3 ★", 0, "0"),
+                ("2 ★", 0, "0"),
+                ("1 ★", 5, "2")
+            ],
+            bars
+        );
+    }
+
+    #[rstest]
+    #[case(3, 4, "Taux de réponse : 3/4 (75 %)")]
+    #[case(4, 4, "Taux de réponse : 4/4 (100 %)")]
+    #[case(6, 4, "Taux de réponse : 4/4 (100 %)")]
+    #[case(0, 3, "Taux de réponse : 0/3 (0 %)")]
+    fn test_get_response_rate_text(
+        #[case] feedbacks_count: usize,
+        #[case] members_count: usize,
+        #[case] expected: &str,
+    ) {
+        // Act
+        let result = get_response_rate_text(feedbacks_count, members_count, Locale::French);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_get_feedback_report_adaptive_card() {
+        // Arrange
+        let feedbacks: Vec<_> = [5, 1, 5]
+            .into_iter()
+            .map(|rating| queries::feedback_query::Feedback {
+                conversation_name: "Sprint review".to_owned(),
+                comment: None,
+                rating,
+                requested_at: Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap(),
+                answered_at: Utc
+                    .with_ymd_and_hms(2026, 10, 18, 12, rating as u32, 0)
+                    .unwrap(),
+            })
+            .collect();
+
+        // Act
+        let result =
+            get_feedback_report_adaptive_card(&feedbacks, false, Some(4), Locale::French).unwrap();
+
+        // Assert
+        assert_eq!("Rapport de feedback", result["body"][0]["text"]);
+        assert_eq!("Sprint review", result["body"][1]["text"]);
+        assert_eq!("Moyenne (3)", result["body"][4]["text"]);
+        assert_eq!("Médiane : 5/5", result["body"][6]["text"]);
+        assert_eq!("Taux de réponse : 3/4 (75 %)", result["body"][7]["text"]);
+        assert_eq!("Répartition", result["body"][8]["text"]);
+        assert_eq!(
+            "Demandé le {{DATE(2026-10-18T12:00:00Z, SHORT)}} à {{TIME(2026-10-18T12:00:00Z)}} · Dernière réponse le {{DATE(2026-10-18T12:05:00Z, SHORT)}} à {{TIME(2026-10-18T12:05:00Z)}}",
+            result["body"][14]["text"]
+        );
+        assert_eq!(15, result["body"].as_array().unwrap().len());
+    }
+
+    #[test]
+    fn test_get_feedback_report_adaptive_card_in_english() {
+        // Arrange
+        let feedbacks = vec![queries::feedback_query::Feedback {
+            conversation_name: "Sprint review".to_owned(),
+            comment: Some("Top".to_owned()),
+            rating: 4,
+            requested_at: Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap(),
+            answered_at: Utc.with_ymd_and_hms(2026, 10, 18, 12, 5, 0).unwrap(),
+        }];
+
+        // Act
+        let result =
+            get_feedback_report_adaptive_card(&feedbacks, true, Some(2), Locale::English).unwrap();
+
+        // Assert
+        let body = &result["body"];
+        assert_eq!("Feedback report", body[0]["text"]);
+        assert_eq!("Sprint review · Anonymous", body[1]["text"]);
+        assert_eq!(
+            "Show the comments (1)",
+            body[2]["columns"][0]["items"][0]["text"]
+        );
+        assert_eq!(
+            "Hide the comments",
+            body[2]["columns"][0]["items"][1]["text"]
+        );
+        assert_eq!("Average (1)", body[4]["text"]);
+        assert_eq!("Median: 4/5", body[6]["text"]);
+        assert_eq!("Response rate: 1/2 (50%)", body[7]["text"]);
+        assert_eq!("Distribution", body[8]["text"]);
+        assert_eq!(
+            "Requested on {{DATE(2026-10-18T12:00:00Z, SHORT)}} at {{TIME(2026-10-18T12:00:00Z)}} · Last answer on {{DATE(2026-10-18T12:05:00Z, SHORT)}} at {{TIME(2026-10-18T12:05:00Z)}}",
+            body[14]["text"]
+        );
+    }
+}
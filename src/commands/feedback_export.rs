@@ -0,0 +1,469 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Acquire, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    database::queries::{self, feedback_query::ExportRow},
+    error::{Error, Result},
+    i18n::Locale,
+    models::{
+        activity::{Activity, Type},
+        attachment::ContentType,
+        file_consent::{FileConsentCard, FileConsentCardResponse, FileInfoCard},
+        Attachment, ConversationAccount, ConversationReference,
+    },
+    services::teams_client::TeamsClient,
+};
+
+use super::{args::FromArgument, feedback_command, send_message, send_response, send_typing};
+
+/// The file format of an export.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+        }
+    }
+}
+
+impl FromArgument for ExportFormat {
+    const EXPECTED: &'static str = "a format among `csv` and `json`";
+    const EXPECTED_FR: &'static str = "un format parmi `csv` et `json`";
+
+    fn from_argument(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportArgs {
+    pub format: ExportFormat,
+    /// Whether the names of the respondents are exported, for the feedbacks which are not anonymous.
+    pub names: bool,
+}
+
+/// Given to the file consent card, to find the export once the owner answers it.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportContext {
+    export_id: String,
+    owner_id: String,
+}
+
+/// The columns of a CSV export, before the one added for each question of the templates.
+const CSV_HEADERS: [&str; 10] = [
+    "feedbackId",
+    "title",
+    "conversationName",
+    "requestedAt",
+    "closesAt",
+    "closedAt",
+    "respondentName",
+    "rating",
+    "comment",
+    "answeredAt",
+];
+
+/// Writes the rows in the given format.
+pub fn build_export(rows: &[ExportRow], format: ExportFormat) -> Result<Vec<u8>> {
+    match format {
+        ExportFormat::Csv => {
+            let questions = get_questions(rows);
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer.write_record(
+                CSV_HEADERS
+                    .iter()
+                    .copied()
+                    .chain(questions.iter().copied())
+                    .map(escape_cell),
+            )?;
+            for row in rows {
+                let mut record = vec![
+                    row.feedback_id.clone(),
+                    row.title.clone().unwrap_or_default(),
+                    row.conversation_name.clone(),
+                    format_date(&row.requested_at),
+                    row.closes_at.as_ref().map(format_date).unwrap_or_default(),
+                    row.closed_at.as_ref().map(format_date).unwrap_or_default(),
+                    row.respondent_name.clone().unwrap_or_default(),
+                    row.rating.to_string(),
+                    row.comment.clone().unwrap_or_default(),
+                    format_date(&row.answered_at),
+                ];
+                record.extend(questions.iter().map(|question| {
+                    row.answers
+                        .iter()
+                        .find(|x| x.question == *question)
+                        .map(|x| x.value.clone())
+                        .unwrap_or_default()
+                }));
+                writer.write_record(record.iter().map(|x| escape_cell(x)))?;
+            }
+            writer
+                .into_inner()
+                .map_err(|e| Error::Csv(e.into_error().into()))
+        }
+        ExportFormat::Json => Ok(serde_json::to_vec_pretty(rows)?),
+    }
+}
+
+/// Returns the questions answered in the rows, in the order they are first met.
+fn get_questions(rows: &[ExportRow]) -> Vec<&str> {
+    let mut questions = Vec::new();
+    for answer in rows.iter().flat_map(|x| &x.answers) {
+        if !questions.contains(&answer.question.as_str()) {
+            questions.push(answer.question.as_str());
+        }
+    }
+    questions
+}
+
+fn format_date(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Prefixes the cells a spreadsheet would read as a formula, since comments and answers are free text.
+fn escape_cell(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_owned()
+    }
+}
+
+/// The name of the exported file, dated so successive exports do not overwrite each other.
+pub fn get_file_name(format: ExportFormat) -> String {
+    format!(
+        "feedbacks-{date}.{extension}",
+        date = Utc::now().format("%Y-%m-%d"),
+        extension = format.extension()
+    )
+}
+
+/// Asks the user, in their personal chat, for the permission to send them the export of their feedbacks in the conversation.
+pub async fn send_export_consent(
+    client: &TeamsClient,
+    pool: &PgPool,
+    activity: &Activity,
+    args: &ExportArgs,
+) -> Result<()> {
+    let user_id = &activity.from.id;
+    let locale = Locale::from(activity);
+
+    send_typing(client, activity).await;
+
+    let rows = queries::feedback_query::get_export_rows(
+        &activity.conversation.id,
+        Some(user_id),
+        args.names,
+        pool,
+    )
+    .await?;
+
+    if rows.is_empty() {
+        let message = match locale {
+            Locale::French => "Vous n'avez aucun feedback à exporter dans cette conversation.",
+            Locale::English => "You have no feedback to export in this conversation.",
+        };
+        return send_message(client, activity, message).await;
+    }
+
+    // Kept until the owner answers, so the uploaded file is the one whose size is announced
+    let content = build_export(&rows, args.format)?;
+    let export_id = Uuid::new_v4().to_string();
+    let context = serde_json::to_value(ExportContext {
+        export_id: export_id.clone(),
+        owner_id: user_id.to_owned(),
+    })?;
+    let description = match locale {
+        Locale::French => "Export des feedbacks",
+        Locale::English => "Feedback export",
+    };
+
+    let mut conn = pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    queries::export_query::delete_expired_exports(&mut *tx).await?;
+    queries::export_query::create_export(&export_id, user_id, &content, &mut *tx).await?;
+
+    let conversation_id = queries::user_query::get_conversation_by_id(user_id, &mut *tx)
+        .await?
+        .flatten();
+    let base_url = activity.service_url.as_deref();
+    let conversation_id = feedback_command::get_or_create_conversation(
+        client,
+        conversation_id,
+        activity,
+        user_id,
+        &mut tx,
+    )
+    .await?;
+
+    let consent = Activity {
+        r#type: Type::Message,
+        attachments: Some(vec![Attachment {
+            content: Some(serde_json::to_value(FileConsentCard {
+                description: Some(description.to_owned()),
+                size_in_bytes: content.len() as u64,
+                accept_context: Some(context.clone()),
+                decline_context: Some(context),
+            })?),
+            content_type: Some(ContentType::FileConsent),
+            name: Some(get_file_name(args.format)),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    };
+    let response = client
+        .send_to_conversation(base_url, &conversation_id, &consent)
+        .await?;
+
+    queries::conversation_reference_query::save_reference(
+        &ConversationReference {
+            activity_id: Some(response.id),
+            conversation: ConversationAccount {
+                id: conversation_id,
+                conversation_type: "personal".to_owned(),
+                tenant_id: activity.conversation.tenant_id.clone(),
+                ..Default::default()
+            },
+            ..activity.get_conversation_reference()
+        },
+        &mut *tx,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    let message = match locale {
+        Locale::French => "L'export vous a été envoyé en privé.",
+        Locale::English => "The export was sent to you in a private chat.",
+    };
+    send_message(client, activity, message).await
+}
+
+/// Handles the answer of the user to the file consent card : uploads the export when accepted, and links to the uploaded file.
+pub async fn handle_file_consent(
+    client: &TeamsClient,
+    pool: &PgPool,
+    activity: &Activity,
+) -> Result<()> {
+    let value: FileConsentCardResponse =
+        serde_json::from_value(activity.value.clone().ok_or(Error::MissingValue("value"))?)?;
+    let context: ExportContext =
+        serde_json::from_value(value.context.ok_or(Error::MissingValue("context"))?)?;
+
+    if context.owner_id != activity.from.id {
+        return Err(Error::UnknownAction(serde_json::to_value(&context)?));
+    }
+
+    let locale = Locale::from(activity);
+    let upload_info = match (value.action.as_str(), value.upload_info) {
+        ("accept", Some(upload_info)) => upload_info,
+        _ => {
+            queries::export_query::delete_export(&context.export_id, pool).await?;
+
+            let message = match locale {
+                Locale::French => "L'export a été annulé.",
+                Locale::English => "The export was cancelled.",
+            };
+            return send_message(client, activity, message).await;
+        }
+    };
+
+    let Some(content) =
+        queries::export_query::get_export(&context.export_id, &context.owner_id, pool).await?
+    else {
+        let message = match locale {
+            Locale::French => "Cet export n'est plus disponible, demandez-en un nouveau.",
+            Locale::English => "This export is no longer available, request a new one.",
+        };
+        return send_message(client, activity, message).await;
+    };
+
+    client.upload_file(&upload_info.upload_url, content).await?;
+    queries::export_query::delete_export(&context.export_id, pool).await?;
+
+    let (_, mut response) = activity.create_response();
+    response.r#type = Type::Message;
+    response.attachments = Some(vec![Attachment {
+        content: Some(serde_json::to_value(FileInfoCard {
+            unique_id: upload_info.unique_id,
+            file_type: upload_info.file_type,
+        })?),
+        content_type: Some(ContentType::FileInfo),
+        content_url: Some(upload_info.content_url),
+        name: Some(upload_info.name),
+    }]);
+
+    send_response(client, activity, &response).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::queries::feedback_query::ExportAnswer;
+    use chrono::{TimeZone, Utc};
+    use rstest::rstest;
+
+    fn rows() -> Vec<ExportRow> {
+        vec![
+            ExportRow {
+                feedback_id: "1".to_owned(),
+                title: Some("Rétro".to_owned()),
+                conversation_name: "Équipe".to_owned(),
+                requested_at: Utc.with_ymd_and_hms(2026, 10, 18, 10, 0, 0).unwrap(),
+                closes_at: None,
+                closed_at: Some(Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap()),
+                respondent_name: Some("John".to_owned()),
+                rating: 4,
+                comment: Some("Bien, merci".to_owned()),
+                answered_at: Utc.with_ymd_and_hms(2026, 10, 18, 11, 0, 0).unwrap(),
+                answers: vec![
+                    ExportAnswer {
+                        question: "Comment s'est passée la rétro ?".to_owned(),
+                        value: "4".to_owned(),
+                    },
+                    ExportAnswer {
+                        question: "Que pourrions-nous améliorer ?".to_owned(),
+                        value: "Les pauses".to_owned(),
+                    },
+                ],
+            },
+            ExportRow {
+                feedback_id: "1".to_owned(),
+                title: Some("Rétro".to_owned()),
+                conversation_name: "Équipe".to_owned(),
+                requested_at: Utc.with_ymd_and_hms(2026, 10, 18, 10, 0, 0).unwrap(),
+                closes_at: None,
+                closed_at: Some(Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap()),
+                respondent_name: None,
+                rating: 2,
+                comment: None,
+                answered_at: Utc.with_ymd_and_hms(2026, 10, 18, 11, 30, 0).unwrap(),
+                answers: vec![ExportAnswer {
+                    question: "Que pourrions-nous améliorer ?".to_owned(),
+                    value: "=HYPERLINK(\"http://example.com\")".to_owned(),
+                }],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_build_export_csv() {
+        // Act
+        let result = build_export(&rows(), ExportFormat::Csv).unwrap();
+
+        // Assert
+        assert_eq!(
+            "feedbackId,title,conversationName,requestedAt,closesAt,closedAt,respondentName,rating,comment,answeredAt,\
+            Comment s'est passée la rétro ?,Que pourrions-nous améliorer ?\n\
+            1,Rétro,Équipe,2026-10-18T10:00:00Z,,2026-10-18T12:00:00Z,John,4,\"Bien, merci\",2026-10-18T11:00:00Z,4,Les pauses\n\
+            1,Rétro,Équipe,2026-10-18T10:00:00Z,,2026-10-18T12:00:00Z,,2,,2026-10-18T11:30:00Z,,\"'=HYPERLINK(\"\"http://example.com\"\")\"\n",
+            String::from_utf8(result).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_export_json() {
+        // Act
+        let result = build_export(&rows(), ExportFormat::Json).unwrap();
+
+        // Assert
+        let value: serde_json::Value = serde_json::from_slice(&result).unwrap();
+        assert_eq!(
+            serde_json::json!({
+                "feedbackId": "1",
+                "title": "Rétro",
+                "conversationName": "Équipe",
+                "requestedAt": "2026-10-18T10:00:00Z",
+                "closesAt": null,
+                "closedAt": "2026-10-18T12:00:00Z",
+                "respondentName": "John",
+                "rating": 4,
+                "comment": "Bien, merci",
+                "answeredAt": "2026-10-18T11:00:00Z",
+                "answers": {
+                    "Comment s'est passée la rétro ?": "4",
+                    "Que pourrions-nous améliorer ?": "Les pauses"
+                }
+            }),
+            value[0]
+        );
+        assert_eq!(2, value.as_array().unwrap().len());
+    }
+
+    #[rstest]
+    #[case("Bien", "Bien")]
+    #[case("=1+1", "'=1+1")]
+    #[case("+33 6", "'+33 6")]
+    #[case("-2", "'-2")]
+    #[case("@SUM(A1)", "'@SUM(A1)")]
+    #[case("", "")]
+    fn test_escape_cell(#[case] value: &str, #[case] expected: &str) {
+        // Act
+        let result = escape_cell(value);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[rstest]
+    #[case("csv", Some(ExportFormat::Csv))]
+    #[case("JSON", Some(ExportFormat::Json))]
+    #[case("xlsx", None)]
+    fn test_format_from_argument(#[case] value: &str, #[case] expected: Option<ExportFormat>) {
+        // Act
+        let result = ExportFormat::from_argument(value);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_export_context_roundtrip() {
+        // Arrange
+        let context = ExportContext {
+            export_id: "0b7e2a4c-5d1f-4e8a-9c3b-6f2d1a0e8b7c".to_owned(),
+            owner_id: "29:owner".to_owned(),
+        };
+
+        // Act
+        let value = serde_json::to_value(&context).unwrap();
+        let result: ExportContext = serde_json::from_value(value.clone()).unwrap();
+
+        // Assert
+        assert_eq!(
+            serde_json::json!({
+                "exportId": "0b7e2a4c-5d1f-4e8a-9c3b-6f2d1a0e8b7c",
+                "ownerId": "29:owner"
+            }),
+            value
+        );
+        assert_eq!(context, result);
+    }
+}
@@ -0,0 +1,532 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::{
+    database::queries::{
+        self,
+        feedback_query::{FeedbackAnswer, FeedbackQuestion},
+    },
+    error::{Error, Result},
+    i18n::Locale,
+    models::{action::CardAction, Action},
+};
+
+use super::{
+    feedback_command::{self, FeedbackCardContent, FeedbackSubmission},
+    router::{ActionHandler, ActionResponse, Context},
+    templates::{QuestionKind, Template},
+};
+
+const YES: &str = "yes";
+const NO: &str = "no";
+
+pub struct FeedbackFormAction;
+
+#[async_trait]
+impl ActionHandler for FeedbackFormAction {
+    async fn handle(&self, context: &Context, action: CardAction) -> Result<ActionResponse> {
+        let Action::FeedbackForm(ref form) = action.action else {
+            return Err(Error::UnknownAction(serde_json::to_value(&action)?));
+        };
+
+        let pool = &context.state.pool;
+        let feedback =
+            queries::feedback_query::get_feedback_card_by_instance_id(&action.instance_id, pool)
+                .await?
+                .ok_or_else(|| {
+                    Error::UnknownAction(serde_json::json!({ "instanceId": action.instance_id }))
+                })?;
+        let questions = queries::feedback_query::get_questions(&feedback.id, pool).await?;
+
+        let Some(submission) = parse_submission(&questions, &form.answers) else {
+            let message = match Locale::from(&context.activity) {
+                Locale::French => "Merci de noter le meeting avant d'envoyer vos réponses.",
+                Locale::English => "Please rate the meeting before sending your answers.",
+            };
+
+            return Ok(ActionResponse::Message(message.to_owned()));
+        };
+
+        let recorded = feedback_command::handle_feedback_entry(
+            &context.state.teams_client,
+            &context.state.graph_client,
+            pool,
+            &context.state.anonymous_secret,
+            &context.activity,
+            &action.instance_id,
+            &submission,
+        )
+        .await?;
+
+        if !recorded {
+            return Ok(feedback_command::get_closed_response(Locale::from(
+                &context.activity,
+            )));
+        }
+
+        feedback_command::get_user_feedback_card(
+            pool,
+            &context.state.anonymous_secret,
+            &action.instance_id,
+            &context.activity.from.id,
+        )
+        .await
+    }
+}
+
+/// The questions of a template, as saved with the feedback in the language of its owner.
+pub fn get_template_questions(template: &Template, locale: Locale) -> Vec<FeedbackQuestion> {
+    template
+        .questions
+        .iter()
+        .zip(0..)
+        .map(|(question, position)| FeedbackQuestion {
+            position,
+            kind: question.kind.as_str().to_owned(),
+            label: question.label.get(locale).to_owned(),
+            choices: question
+                .choices
+                .iter()
+                .map(|x| x.get(locale).to_owned())
+                .collect(),
+        })
+        .collect()
+}
+
+fn get_input_id(position: i32) -> String {
+    format!("q{position}")
+}
+
+/// Reads the values of the form, ignoring the empty and invalid ones. Returns `None` when the overall rating, the first rating question, is missing.
+fn parse_submission(
+    questions: &[FeedbackQuestion],
+    values: &HashMap<String, String>,
+) -> Option<FeedbackSubmission> {
+    let answers: Vec<_> = questions
+        .iter()
+        .filter_map(|question| {
+            let value = values
+                .get(&get_input_id(question.position))
+                .map(|x| x.trim())
+                .filter(|x| !x.is_empty())?;
+
+            let valid = match QuestionKind::parse(&question.kind)? {
+                QuestionKind::Rating => value.parse().is_ok_and(|x: i32| (1..=5).contains(&x)),
+                QuestionKind::YesNo => value == YES || value == NO,
+                QuestionKind::Choice => question.choices.iter().any(|x| x == value),
+                QuestionKind::Text => true,
+            };
+
+            valid.then(|| FeedbackAnswer {
+                position: question.position,
+                value: value.to_owned(),
+            })
+        })
+        .collect();
+
+    let rating_position = questions
+        .iter()
+        .find(|x| QuestionKind::parse(&x.kind) == Some(QuestionKind::Rating))?
+        .position;
+    let rating = answers
+        .iter()
+        .find(|x| x.position == rating_position)?
+        .value
+        .parse()
+        .ok()?;
+
+    Some(FeedbackSubmission {
+        rating,
+        comment: None,
+        answers,
+    })
+}
+
+/// The form of a feedback created from a template, filled with the answers of the user viewing it.
+pub(super) fn get_feedback_form_adaptive_card(
+    content: &FeedbackCardContent,
+    questions: &[FeedbackQuestion],
+    answers: &[FeedbackAnswer],
+) -> serde_json::Value {
+    let locale = content.locale;
+    let mut body = vec![
+        serde_json::json!({
+            "type": "TextBlock",
+            "text": content
+                .title
+                .unwrap_or(feedback_command::get_fallback_title(locale)),
+            "wrap": true,
+            "style": "heading"
+        }),
+        serde_json::json!({
+            "type": "TextBlock",
+            "text": feedback_command::get_subtitle(content.owner_name, content.anonymous, locale),
+            "wrap": true,
+            "isSubtle": true
+        }),
+    ];
+
+    body.extend(questions.iter().filter_map(|question| {
+        let value = answers
+            .iter()
+            .find(|x| x.position == question.position)
+            .map(|x| x.value.as_str());
+
+        get_question_input(question, value, locale)
+    }));
+
+    let status = match (content.closed, content.closes_at) {
+        (true, _) => Some(feedback_command::get_closed_text(locale).to_owned()),
+        (false, Some(closes_at)) => Some(feedback_command::get_deadline_text(closes_at, locale)),
+        (false, None) => None,
+    };
+    if let Some(status) = status {
+        body.push(serde_json::json!({
+            "type": "TextBlock",
+            "text": status,
+            "wrap": true,
+            "isSubtle": true,
+            "horizontalAlignment": "Center"
+        }));
+    }
+
+    let actions = match content.closed {
+        true => Vec::new(),
+        false => vec![serde_json::json!({
+            "type": "Action.Execute",
+            "title": match locale {
+                Locale::French => "Envoyer",
+                Locale::English => "Send",
+            },
+            "verb": Action::FEEDBACK_FORM,
+            "data": { "instanceId": content.instance_id }
+        })],
+    };
+
+    serde_json::json!({
+        "type": "AdaptiveCard",
+        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+        "version": "1.5",
+        "refresh": {
+            "action": {
+                "type": "Action.Execute",
+                "verb": Action::FEEDBACK_REFRESH,
+                "data": { "instanceId": content.instance_id }
+            }
+        },
+        "body": body,
+        "actions": actions
+    })
+}
+
+fn get_question_input(
+    question: &FeedbackQuestion,
+    value: Option<&str>,
+    locale: Locale,
+) -> Option<serde_json::Value> {
+    let (required, yes, no, placeholder) = match locale {
+        Locale::French => (
+            "Une note est obligatoire.",
+            "Oui",
+            "Non",
+            "Votre réponse ...",
+        ),
+        Locale::English => ("A rating is required.", "Yes", "No", "Your answer ..."),
+    };
+
+    let mut input = match QuestionKind::parse(&question.kind)? {
+        QuestionKind::Rating => serde_json::json!({
+            "type": "Input.ChoiceSet",
+            "style": "compact",
+            "isRequired": true,
+            "errorMessage": required,
+            "choices": (1..=5)
+                .map(|x| serde_json::json!({
+                    "title": format!("{}{}", "★".repeat(x), "☆".repeat(5 - x)),
+                    "value": x.to_string()
+                }))
+                .collect::<Vec<_>>()
+        }),
+        QuestionKind::YesNo => serde_json::json!({
+            "type": "Input.ChoiceSet",
+            "style": "expanded",
+            "choices": [
+                { "title": yes, "value": YES },
+                { "title": no, "value": NO }
+            ]
+        }),
+        QuestionKind::Choice => serde_json::json!({
+            "type": "Input.ChoiceSet",
+            "style": "expanded",
+            "choices": question
+                .choices
+                .iter()
+                .map(|x| serde_json::json!({ "title": x, "value": x }))
+                .collect::<Vec<_>>()
+        }),
+        QuestionKind::Text => serde_json::json!({
+            "type": "Input.Text",
+            "placeholder": placeholder,
+            "isMultiline": true
+        }),
+    };
+
+    input["id"] = serde_json::json!(get_input_id(question.position));
+    input["label"] = serde_json::json!(question.label);
+    input["separator"] = serde_json::json!(true);
+    if let Some(value) = value {
+        input["value"] = serde_json::json!(value);
+    }
+
+    Some(input)
+}
+
+/// Summarizes the answers of each question, appended to the report of the feedback.
+pub(super) fn get_questions_report(
+    questions: &[FeedbackQuestion],
+    answers: &[FeedbackAnswer],
+    locale: Locale,
+) -> Vec<serde_json::Value> {
+    let no_answer = match locale {
+        Locale::French => "Aucune réponse",
+        Locale::English => "No answer",
+    };
+
+    questions
+        .iter()
+        .filter_map(|question| {
+            let values: Vec<_> = answers
+                .iter()
+                .filter(|x| x.position == question.position)
+                .map(|x| x.value.as_str())
+                .collect();
+
+            let summary = match QuestionKind::parse(&question.kind)? {
+                QuestionKind::Rating => {
+                    let ratings: Vec<f32> = values.iter().filter_map(|x| x.parse().ok()).collect();
+                    match ratings.len() {
+                        0 => vec![no_answer.to_owned()],
+                        count => {
+                            let average = ratings.iter().sum::<f32>() / count as f32;
+                            vec![match locale {
+                                Locale::French => {
+                                    format!("Moyenne : {average:.1}/5 ({count} réponse(s))")
+                                }
+                                Locale::English => {
+                                    format!("Average: {average:.1}/5 ({count} answer(s))")
+                                }
+                            }]
+                        }
+                    }
+                }
+                QuestionKind::YesNo => {
+                    let yes = values.iter().filter(|x| **x == YES).count();
+                    let no = values.iter().filter(|x| **x == NO).count();
+                    vec![match locale {
+                        Locale::French => format!("Oui : {yes} · Non : {no}"),
+                        Locale::English => format!("Yes: {yes} · No: {no}"),
+                    }]
+                }
+                QuestionKind::Choice => vec![question
+                    .choices
+                    .iter()
+                    .map(|choice| {
+                        let count = values.iter().filter(|x| *x == choice).count();
+                        match locale {
+                            Locale::French => format!("{choice} : {count}"),
+                            Locale::English => format!("{choice}: {count}"),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" · ")],
+                QuestionKind::Text => match values.is_empty() {
+                    true => vec![no_answer.to_owned()],
+                    false => values
+                        .iter()
+                        .map(|x| match locale {
+                            Locale::French => format!("« {x} »"),
+                            Locale::English => format!("“{x}”"),
+                        })
+                        .collect(),
+                },
+            };
+
+            let mut items = vec![serde_json::json!({
+                "type": "TextBlock",
+                "text": question.label,
+                "wrap": true,
+                "weight": "Bolder"
+            })];
+            items.extend(summary.into_iter().map(|text| {
+                serde_json::json!({
+                    "type": "TextBlock",
+                    "text": text,
+                    "wrap": true,
+                    "spacing": "Small"
+                })
+            }));
+
+            Some(serde_json::json!({
+                "type": "Container",
+                "separator": true,
+                "items": items
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::templates::RETRO;
+    use rstest::rstest;
+
+    fn answers(values: &[(i32, &str)]) -> Vec<FeedbackAnswer> {
+        values
+            .iter()
+            .map(|(position, value)| FeedbackAnswer {
+                position: *position,
+                value: value.to_string(),
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case(&[("q0", "4"), ("q1", "yes"), ("q2", "Adapté"), ("q3", "Top")], Some((4, answers(&[(0, "4"), (1, "yes"), (2, "Adapté"), (3, "Top")]))))]
+    #[case(&[("q0", "4"), ("q1", "maybe"), ("q2", "Unknown"), ("q3", " ")], Some((4, answers(&[(0, "4")]))))]
+    #[case(&[("q0", "6"), ("q1", "yes")], None)]
+    #[case(&[("q1", "yes")], None)]
+    fn test_parse_submission(
+        #[case] values: &[(&str, &str)],
+        #[case] expected: Option<(i32, Vec<FeedbackAnswer>)>,
+    ) {
+        // Arrange
+        let questions = get_template_questions(&RETRO, Locale::French);
+        let values = values
+            .iter()
+            .map(|(id, value)| (id.to_string(), value.to_string()))
+            .collect();
+
+        // Act
+        let result = parse_submission(&questions, &values);
+
+        // Assert
+        assert_eq!(expected, result.map(|x| (x.rating, x.answers)));
+    }
+
+    #[test]
+    fn test_get_feedback_form_adaptive_card() {
+        // Arrange
+        let questions = get_template_questions(&RETRO, Locale::French);
+        let content = FeedbackCardContent {
+            instance_id: "42",
+            title: Some("Rétro"),
+            owner_name: "John",
+            anonymous: false,
+            closes_at: None,
+            closed: false,
+            locale: Locale::French,
+        };
+
+        // Act
+        let result = get_feedback_form_adaptive_card(&content, &questions, &answers(&[(0, "3")]));
+
+        // Assert
+        assert_eq!("Rétro", result["body"][0]["text"]);
+        assert_eq!("q0", result["body"][2]["id"]);
+        assert_eq!("3", result["body"][2]["value"]);
+        assert_eq!(RETRO.questions[3].label.french, result["body"][5]["label"]);
+        assert!(result["body"][5].get("value").is_none());
+        assert_eq!(Action::FEEDBACK_FORM, result["actions"][0]["verb"]);
+        assert_eq!("42", result["actions"][0]["data"]["instanceId"]);
+        assert_eq!("42", result["refresh"]["action"]["data"]["instanceId"]);
+    }
+
+    #[test]
+    fn test_get_feedback_form_adaptive_card_closed() {
+        // Arrange
+        let questions = get_template_questions(&RETRO, Locale::French);
+        let content = FeedbackCardContent {
+            instance_id: "42",
+            title: None,
+            owner_name: "John",
+            anonymous: false,
+            closes_at: None,
+            closed: true,
+            locale: Locale::French,
+        };
+
+        // Act
+        let result = get_feedback_form_adaptive_card(&content, &questions, &[]);
+
+        // Assert
+        assert_eq!(
+            feedback_command::get_closed_text(Locale::French),
+            result["body"][6]["text"]
+        );
+        assert_eq!(0, result["actions"].as_array().unwrap().len());
+    }
+
+    #[test]
+    fn test_get_questions_report() {
+        // Arrange
+        let questions = get_template_questions(&RETRO, Locale::French);
+        let answers = answers(&[
+            (0, "4"),
+            (0, "3"),
+            (1, "yes"),
+            (1, "yes"),
+            (1, "no"),
+            (2, "Adapté"),
+            (3, "Plus court"),
+        ]);
+
+        // Act
+        let result = get_questions_report(&questions, &answers, Locale::French);
+
+        // Assert
+        let summaries: Vec<_> = result
+            .iter()
+            .map(|x| x["items"][1]["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            vec![
+                "Moyenne : 3.5/5 (2 réponse(s))",
+                "Oui : 2 · Non : 1",
+                "Trop lent : 0 · Adapté : 1 · Trop rapide : 0",
+                "« Plus court »"
+            ],
+            summaries
+        );
+    }
+
+    #[test]
+    fn test_get_questions_report_in_english() {
+        // Arrange
+        let questions = get_template_questions(&RETRO, Locale::English);
+        let answers = answers(&[(0, "4"), (1, "no"), (2, "Right")]);
+
+        // Act
+        let result = get_questions_report(&questions, &answers, Locale::English);
+
+        // Assert
+        let summaries: Vec<_> = result
+            .iter()
+            .map(|x| x["items"][1]["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            vec![
+                "Average: 4.0/5 (1 answer(s))",
+                "Yes: 0 · No: 1",
+                "Too slow: 0 · Right: 1 · Too fast: 0",
+                "No answer"
+            ],
+            summaries
+        );
+        assert_eq!(
+            RETRO.questions[0].label.english,
+            result[0]["items"][0]["text"]
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use chrono::SecondsFormat;
+use sqlx::PgPool;
+
+use crate::{
+    database::queries::{self, feedback_query::FeedbackSession},
+    error::Result,
+    i18n::Locale,
+    models::{activity::Activity, ConversationReference},
+    services::teams_client::TeamsClient,
+};
+
+use super::{feedback_command, send_adaptive_card, send_message};
+
+/// The number of sessions shown when `--last` is missing.
+const DEFAULT_SESSIONS: u32 = 5;
+/// The most sessions a card can show, to keep it readable.
+const MAX_SESSIONS: u32 = 20;
+/// The number of blocks of the bar of a 5 stars session.
+const BAR_WIDTH: f64 = 10.0;
+/// The smallest change of the average considered as a trend rather than noise.
+const TREND_THRESHOLD: f64 = 0.25;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct HistoryArgs {
+    /// The number of sessions to show, [`DEFAULT_SESSIONS`] when missing.
+    pub last: Option<u32>,
+}
+
+/// How the last session compares to the previous ones.
+#[derive(Debug, PartialEq)]
+enum Trend {
+    Up,
+    Down,
+    Stable,
+}
+
+/// Sends the trend of the last feedbacks of the conversation, a recurring meeting keeping the same conversation across its sessions.
+pub async fn send_history_card(
+    client: &TeamsClient,
+    pool: &PgPool,
+    activity: &Activity,
+    args: &HistoryArgs,
+) -> Result<()> {
+    let last = args.last.unwrap_or(DEFAULT_SESSIONS).clamp(1, MAX_SESSIONS);
+    let sessions =
+        queries::feedback_query::get_sessions(&activity.conversation.id, last.into(), pool).await?;
+
+    let locale = Locale::from(activity);
+    if sessions.is_empty() {
+        let message = match locale {
+            Locale::French => "Aucun feedback n'a encore été demandé dans cette conversation.",
+            Locale::English => "No feedback was requested in this conversation yet.",
+        };
+        return send_message(client, activity, message).await;
+    }
+
+    let card = get_history_adaptive_card(&sessions, locale);
+    let response = send_adaptive_card(client, activity, &card).await?;
+
+    queries::conversation_reference_query::save_reference(
+        &ConversationReference {
+            activity_id: Some(response.id),
+            ..activity.get_conversation_reference()
+        },
+        pool,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Compares the average of the most recent session to the average of the previous ones. Sessions nobody answered are ignored.
+fn get_trend(sessions: &[FeedbackSession]) -> Option<Trend> {
+    let mut averages = sessions.iter().filter_map(|x| x.average);
+    let latest = averages.next()?;
+    let previous: Vec<_> = averages.collect();
+    if previous.is_empty() {
+        return None;
+    }
+
+    let difference = latest - previous.iter().sum::<f64>() / previous.len() as f64;
+    let trend = match difference {
+        x if x >= TREND_THRESHOLD => Trend::Up,
+        x if x <= -TREND_THRESHOLD => Trend::Down,
+        _ => Trend::Stable,
+    };
+
+    Some(trend)
+}
+
+/// Renders the sessions, the most recent first, with the average rating and the number of responses over all of them.
+fn get_history_adaptive_card(sessions: &[FeedbackSession], locale: Locale) -> serde_json::Value {
+    let responses_count: i64 = sessions.iter().map(|x| x.responses_count).sum();
+    let ratings_sum: f64 = sessions
+        .iter()
+        .filter_map(|x| x.average.map(|average| average * x.responses_count as f64))
+        .sum();
+    let name = sessions
+        .first()
+        .map(|x| x.conversation_name.as_str())
+        .unwrap_or_default();
+
+    let mut body = vec![
+        serde_json::json!({
+            "type": "TextBlock",
+            "text": match locale {
+                Locale::French => "Historique des feedbacks",
+                Locale::English => "Feedback history",
+            },
+            "wrap": true,
+            "style": "heading"
+        }),
+        serde_json::json!({
+            "type": "TextBlock",
+            "text": name,
+            "wrap": true,
+            "isSubtle": true
+        }),
+    ];
+
+    let sessions_count = sessions.len();
+    let mut summary = Vec::with_capacity(3);
+    if responses_count > 0 {
+        let average = ratings_sum / responses_count as f64;
+        summary.push(match locale {
+            Locale::French => {
+                format!("Moyenne sur les {sessions_count} dernières sessions : {average:.1}/5")
+            }
+            Locale::English => {
+                format!("Average over the last {sessions_count} sessions: {average:.1}/5")
+            }
+        });
+    }
+    let per_session = responses_count as f64 / sessions_count as f64;
+    summary.push(match locale {
+        Locale::French => format!("Réponses : {responses_count} ({per_session:.1} par session)"),
+        Locale::English => format!("Answers: {responses_count} ({per_session:.1} per session)"),
+    });
+    if let Some(trend) = get_trend(sessions) {
+        summary.push(
+            match (trend, locale) {
+                (Trend::Up, Locale::French) => "Tendance : ↗ en hausse",
+                (Trend::Down, Locale::French) => "Tendance : ↘ en baisse",
+                (Trend::Stable, Locale::French) => "Tendance : → stable",
+                (Trend::Up, Locale::English) => "Trend: ↗ rising",
+                (Trend::Down, Locale::English) => "Trend: ↘ falling",
+                (Trend::Stable, Locale::English) => "Trend: → stable",
+            }
+            .to_owned(),
+        );
+    }
+    body.extend(summary.into_iter().map(|text| {
+        serde_json::json!({
+            "type": "TextBlock",
+            "text": text,
+            "wrap": true
+        })
+    }));
+
+    body.push(serde_json::json!({
+        "type": "TextBlock",
+        "text": "Sessions",
+        "wrap": true,
+        "style": "heading",
+        "separator": true,
+        "spacing": "ExtraLarge"
+    }));
+    body.extend(sessions.iter().map(|x| get_session_row(x, locale)));
+
+    serde_json::json!({
+        "type": "AdaptiveCard",
+        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+        "version": "1.5",
+        "body": body
+    })
+}
+
+fn get_session_row(session: &FeedbackSession, locale: Locale) -> serde_json::Value {
+    let date = format!(
+        "{{{{DATE({iso}, SHORT)}}}}",
+        iso = session
+            .created_at
+            .to_rfc3339_opts(SecondsFormat::Secs, true)
+    );
+    let (bar, rating) = match session.average {
+        Some(average) => (
+            "█".repeat((average * BAR_WIDTH / 5.0).round() as usize),
+            format!("{average:.1} ★ ({})", session.responses_count),
+        ),
+        None => (String::new(), "—".to_owned()),
+    };
+
+    serde_json::json!({
+        "type": "ColumnSet",
+        "spacing": "Small",
+        "columns": [
+            {
+                "type": "Column",
+                "width": "auto",
+                "items": [
+                    { "type": "TextBlock", "text": date, "isSubtle": true },
+                    {
+                        "type": "TextBlock",
+                        "text": session
+                            .title
+                            .as_deref()
+                            .unwrap_or(feedback_command::get_fallback_title(locale)),
+                        "wrap": true,
+                        "spacing": "None"
+                    }
+                ]
+            },
+            {
+                "type": "Column",
+                "width": "stretch",
+                "verticalContentAlignment": "Center",
+                "items": [{ "type": "TextBlock", "text": bar, "color": "Accent" }]
+            },
+            {
+                "type": "Column",
+                "width": "auto",
+                "verticalContentAlignment": "Center",
+                "items": [{ "type": "TextBlock", "text": rating }]
+            }
+        ]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rstest::rstest;
+
+    fn session(average: Option<f64>, responses_count: i64) -> FeedbackSession {
+        FeedbackSession {
+            id: "1".to_owned(),
+            title: None,
+            conversation_name: "Rétro hebdo".to_owned(),
+            created_at: Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap(),
+            responses_count,
+            average,
+        }
+    }
+
+    #[rstest]
+    #[case(&[Some(4.0), Some(3.0), Some(3.5)], Some(Trend::Up))]
+    #[case(&[Some(3.0), Some(4.0)], Some(Trend::Down))]
+    #[case(&[Some(3.6), Some(3.5)], Some(Trend::Stable))]
+    #[case(&[None, Some(2.0), Some(4.0)], Some(Trend::Down))]
+    #[case(&[Some(4.0), None], None)]
+    #[case(&[], None)]
+    fn test_get_trend(#[case] averages: &[Option<f64>], #[case] expected: Option<Trend>) {
+        // Arrange
+        let sessions: Vec<_> = averages.iter().map(|x| session(*x, 1)).collect();
+
+        // Act
+        let result = get_trend(&sessions);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_get_history_adaptive_card() {
+        // Arrange
+        let sessions = [
+            session(Some(4.0), 3),
+            session(Some(2.0), 1),
+            session(None, 0),
+        ];
+
+        // Act
+        let result = get_history_adaptive_card(&sessions, Locale::French);
+
+        // Assert
+        let body = result["body"].as_array().unwrap();
+        assert_eq!("Rétro hebdo", body[1]["text"]);
+        assert_eq!(
+            "Moyenne sur les 3 dernières sessions : 3.5/5",
+            body[2]["text"]
+        );
+        assert_eq!("Réponses : 4 (1.3 par session)", body[3]["text"]);
+        assert_eq!("Tendance : ↗ en hausse", body[4]["text"]);
+        assert_eq!(9, body.len());
+        assert_eq!(
+            "{{DATE(2026-10-18T12:00:00Z, SHORT)}}",
+            body[6]["columns"][0]["items"][0]["text"]
+        );
+        assert_eq!(
+            "Demande de feedback",
+            body[6]["columns"][0]["items"][1]["text"]
+        );
+        assert_eq!("████████", body[6]["columns"][1]["items"][0]["text"]);
+        assert_eq!("4.0 ★ (3)", body[6]["columns"][2]["items"][0]["text"]);
+        assert_eq!("—", body[8]["columns"][2]["items"][0]["text"]);
+    }
+
+    #[test]
+    fn test_get_history_adaptive_card_without_responses() {
+        // Arrange
+        let sessions = [session(None, 0)];
+
+        // Act
+        let result = get_history_adaptive_card(&sessions, Locale::French);
+
+        // Assert
+        let body = result["body"].as_array().unwrap();
+        assert_eq!("Réponses : 0 (0.0 par session)", body[2]["text"]);
+        assert_eq!("Sessions", body[3]["text"]);
+    }
+
+    #[test]
+    fn test_get_history_adaptive_card_in_english() {
+        // Arrange
+        let sessions = [session(Some(2.0), 1), session(Some(4.0), 3)];
+
+        // Act
+        let result = get_history_adaptive_card(&sessions, Locale::English);
+
+        // Assert
+        let body = result["body"].as_array().unwrap();
+        assert_eq!("Feedback history", body[0]["text"]);
+        assert_eq!("Average over the last 2 sessions: 3.5/5", body[2]["text"]);
+        assert_eq!("Answers: 4 (2.0 per session)", body[3]["text"]);
+        assert_eq!("Trend: ↘ falling", body[4]["text"]);
+        assert_eq!(
+            "Feedback request",
+            body[6]["columns"][0]["items"][1]["text"]
+        );
+    }
+}
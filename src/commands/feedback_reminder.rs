@@ -0,0 +1,465 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::warn;
+
+use crate::{
+    database::queries::{self, feedback_query::FeedbackCard},
+    error::{Error, Result},
+    i18n::Locale,
+    models::{
+        activity::{Activity, Type},
+        attachment::ContentType,
+        Attachment, ConversationAccount, ConversationReference,
+    },
+    scheduler::{Job, JobHandler},
+    services::{graph_client::GraphClient, proactive, teams_client::TeamsClient},
+    state::AppState,
+    utils,
+};
+
+use super::{
+    feedback_command,
+    meeting::{self, FeedbackMeeting},
+    send_message, send_typing,
+};
+
+/// Reminds the members who did not answer a feedback, enqueued with the feedback when `--remind-in` is given.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackReminder {
+    pub instance_id: String,
+}
+
+impl Job for FeedbackReminder {
+    const KIND: &'static str = "feedbackReminder";
+}
+
+pub struct FeedbackReminderJob;
+
+#[async_trait]
+impl JobHandler for FeedbackReminderJob {
+    async fn run(&self, state: &AppState, payload: serde_json::Value) -> Result<()> {
+        let FeedbackReminder { instance_id } = serde_json::from_value(payload)?;
+
+        remind_non_respondents(
+            &state.teams_client,
+            &state.graph_client,
+            &state.pool,
+            &state.anonymous_secret,
+            &instance_id,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Reminds the members who did not answer the open feedbacks of the user in the conversation.
+pub async fn send_reminders(
+    client: &TeamsClient,
+    graph_client: &GraphClient,
+    pool: &PgPool,
+    secret: &str,
+    activity: &Activity,
+) -> Result<()> {
+    send_typing(client, activity).await;
+
+    let instance_ids = queries::feedback_query::get_open_feedbacks(
+        &activity.from.id,
+        &activity.conversation.id,
+        pool,
+    )
+    .await?;
+
+    let mut reminded = 0;
+    for instance_id in &instance_ids {
+        reminded += remind_non_respondents(client, graph_client, pool, secret, instance_id).await?;
+    }
+
+    let message = match (instance_ids.is_empty(), Locale::from(activity)) {
+        (true, Locale::French) => {
+            "Vous n'avez aucune demande de feedback ouverte dans cette conversation.".to_owned()
+        }
+        (true, Locale::English) => {
+            "You have no open feedback request in this conversation.".to_owned()
+        }
+        (false, Locale::French) => format!("{reminded} rappel(s) envoyé(s)."),
+        (false, Locale::English) => format!("{reminded} reminder(s) sent."),
+    };
+
+    send_message(client, activity, &message).await
+}
+
+/// Sends a personal message to every member of the conversation of the feedback who did not answer it, but its owner, the roster being read page by page. When the feedback was requested in a meeting whose attendance is known, only the attendees of its session are reminded. Returns the number of members reminded. A member who cannot be reached is only logged, and so is a page of the roster which cannot be read once some members were reminded, since retrying the job would remind them again.
+pub async fn remind_non_respondents(
+    client: &TeamsClient,
+    graph_client: &GraphClient,
+    pool: &PgPool,
+    secret: &str,
+    instance_id: &str,
+) -> Result<usize> {
+    let Some(card) =
+        queries::feedback_query::get_feedback_card_by_instance_id(instance_id, pool).await?
+    else {
+        return Ok(0);
+    };
+    if card.closed {
+        return Ok(0);
+    }
+
+    let Some(reference) =
+        queries::conversation_reference_query::get_reference(&card.id, pool).await?
+    else {
+        warn!("The conversation of the feedback {instance_id} is unknown, nobody is reminded");
+        return Ok(0);
+    };
+
+    let respondent_keys: HashSet<_> = queries::feedback_query::get_respondent_keys(&card.id, pool)
+        .await?
+        .into_iter()
+        .collect();
+    let bot_id = reference.bot.as_ref().map(|x| x.id.as_str());
+    let attendee_ids = meeting::get_attendee_ids(
+        graph_client,
+        pool,
+        &FeedbackMeeting {
+            feedback_id: &card.id,
+            meeting_id: card.meeting_id.as_deref(),
+            organizer_id: card.organizer_id.as_deref(),
+            created_at: card.created_at,
+            attendance_report_id: card.attendance_report_id.as_deref(),
+            attendance_checked_at: card.attendance_checked_at,
+        },
+    )
+    .await;
+
+    let reminder = get_reminder_activity(&card, &reference);
+    let mut reminded = 0;
+    let mut continuation_token = None;
+
+    loop {
+        let page = match client
+            .get_paged_members(
+                reference.service_url.as_deref(),
+                &reference.conversation.id,
+                None,
+                continuation_token.as_deref(),
+            )
+            .await
+        {
+            Ok(page) => page,
+            Err(e) => return end_on_page_error(instance_id, reminded, e),
+        };
+
+        for member in page.members {
+            let respondent_key =
+                utils::respondent_key(secret, &card.id, &member.id, card.anonymous);
+            if Some(member.id.as_str()) == bot_id
+                || member.id == card.owner_id
+                || respondent_keys.contains(&respondent_key)
+                || !is_attendee(attendee_ids.as_ref(), member.aad_object_id.as_deref())
+            {
+                continue;
+            }
+
+            match send_reminder(client, pool, &reference, &member.id, &reminder).await {
+                Ok(()) => reminded += 1,
+                Err(e) => warn!(
+                    "An error occured while reminding {} of the feedback {instance_id} : {:?}",
+                    member.id, e
+                ),
+            }
+        }
+
+        continuation_token = page.continuation_token;
+        if continuation_token.is_none() {
+            return Ok(reminded);
+        }
+    }
+}
+
+/// Fails on a roster page which cannot be read only when nobody was reminded yet, so the job is retried without reminding anybody twice.
+fn end_on_page_error(instance_id: &str, reminded: usize, error: Error) -> Result<usize> {
+    if reminded == 0 {
+        return Err(error);
+    }
+
+    warn!(
+        "An error occured while reading the members to remind of the feedback {instance_id}, {reminded} member(s) reminded : {:?}",
+        error
+    );
+    Ok(reminded)
+}
+
+/// Whether the member attended the meeting, everyone being considered an attendee when the attendance is unknown.
+fn is_attendee(attendee_ids: Option<&HashSet<String>>, aad_object_id: Option<&str>) -> bool {
+    match attendee_ids {
+        Some(attendee_ids) => aad_object_id.is_some_and(|x| attendee_ids.contains(x)),
+        None => true,
+    }
+}
+
+/// Sends the reminder in the personal chat of the member, saving where it was posted.
+async fn send_reminder(
+    client: &TeamsClient,
+    pool: &PgPool,
+    reference: &ConversationReference,
+    user_id: &str,
+    reminder: &Activity,
+) -> Result<()> {
+    let conversation_id = feedback_command::create_conversation(client, reference, user_id).await?;
+
+    let personal = ConversationReference {
+        activity_id: None,
+        conversation: ConversationAccount {
+            id: conversation_id,
+            conversation_type: "personal".to_owned(),
+            tenant_id: reference.conversation.tenant_id.clone(),
+            ..Default::default()
+        },
+        ..reference.clone()
+    };
+    let response = proactive::send_to_reference(client, &personal, reminder).await?;
+
+    queries::conversation_reference_query::save_reference(
+        &ConversationReference {
+            activity_id: Some(response.id),
+            ..personal
+        },
+        pool,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// The link opening the card of the feedback in its conversation.
+fn get_card_url(reference: &ConversationReference) -> String {
+    let message_id = reference.activity_id.as_deref().unwrap_or_default();
+
+    match reference.conversation.conversation_type.as_str() {
+        "channel" => format!(
+            "https://teams.microsoft.com/l/message/{}/{message_id}",
+            reference.conversation.id
+        ),
+        _ => format!(
+            "https://teams.microsoft.com/l/message/{}/{message_id}?context=%7B%22contextType%22%3A%22chat%22%7D",
+            reference.conversation.id
+        ),
+    }
+}
+
+/// The reminder is written in the language of the feedback, like its card.
+fn get_reminder_activity(card: &FeedbackCard, reference: &ConversationReference) -> Activity {
+    let locale = Locale::parse(&card.locale);
+    let title = card
+        .title
+        .as_deref()
+        .unwrap_or(feedback_command::get_fallback_title(locale));
+    let owner_name = card.owner_name.as_deref().unwrap_or_default();
+
+    let (heading, text, answer) = match locale {
+        Locale::French => (
+            "Votre avis compte !",
+            format!("{owner_name} attend votre feedback sur « {title} »."),
+            "Répondre",
+        ),
+        Locale::English => (
+            "Your opinion matters!",
+            format!("{owner_name} is waiting for your feedback on “{title}”."),
+            "Answer",
+        ),
+    };
+
+    let mut body = vec![
+        serde_json::json!({
+            "type": "TextBlock",
+            "text": heading,
+            "wrap": true,
+            "style": "heading"
+        }),
+        serde_json::json!({
+            "type": "TextBlock",
+            "text": text,
+            "wrap": true
+        }),
+    ];
+    if let Some(ref closes_at) = card.closes_at {
+        body.push(serde_json::json!({
+            "type": "TextBlock",
+            "text": feedback_command::get_deadline_text(closes_at, locale),
+            "wrap": true,
+            "isSubtle": true
+        }));
+    }
+
+    let content = serde_json::json!({
+        "type": "AdaptiveCard",
+        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+        "version": "1.5",
+        "body": body,
+        "actions": [
+            {
+                "type": "Action.OpenUrl",
+                "title": answer,
+                "url": get_card_url(reference)
+            }
+        ]
+    });
+
+    Activity {
+        r#type: Type::Message,
+        attachments: Some(vec![Attachment {
+            content: Some(content),
+            content_type: Some(ContentType::Adaptive),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rstest::rstest;
+
+    fn reference(conversation_type: &str) -> ConversationReference {
+        ConversationReference {
+            activity_id: Some("1700000000000".to_owned()),
+            conversation: ConversationAccount {
+                id: "19:meeting@thread.v2".to_owned(),
+                conversation_type: conversation_type.to_owned(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case(
+        "groupChat",
+        "https://teams.microsoft.com/l/message/19:meeting@thread.v2/1700000000000?context=%7B%22contextType%22%3A%22chat%22%7D"
+    )]
+    #[case(
+        "channel",
+        "https://teams.microsoft.com/l/message/19:meeting@thread.v2/1700000000000"
+    )]
+    fn test_get_card_url(#[case] conversation_type: &str, #[case] expected: &str) {
+        // Act
+        let result = get_card_url(&reference(conversation_type));
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_end_on_page_error_after_reminders() {
+        // Act
+        let result = end_on_page_error(
+            "1700000000000",
+            3,
+            Error::Service(serde_json::json!({"error": "TooManyRequests"})),
+        );
+
+        // Assert
+        assert_eq!(3, result.unwrap());
+    }
+
+    #[test]
+    fn test_end_on_page_error_before_reminders() {
+        // Act
+        let result = end_on_page_error(
+            "1700000000000",
+            0,
+            Error::Service(serde_json::json!({"error": "TooManyRequests"})),
+        );
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    #[case(None, None, true)]
+    #[case(Some(vec!["8c1d2e3f"]), Some("8c1d2e3f"), true)]
+    #[case(Some(vec!["8c1d2e3f"]), Some("6b2e4c3a"), false)]
+    #[case(Some(vec!["8c1d2e3f"]), None, false)]
+    fn test_is_attendee(
+        #[case] attendee_ids: Option<Vec<&str>>,
+        #[case] aad_object_id: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        // Arrange
+        let attendee_ids: Option<HashSet<String>> =
+            attendee_ids.map(|x| x.into_iter().map(str::to_owned).collect());
+
+        // Act
+        let result = is_attendee(attendee_ids.as_ref(), aad_object_id);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    fn card(locale: &str) -> FeedbackCard {
+        FeedbackCard {
+            id: "1700000000000".to_owned(),
+            title: Some("Rétro".to_owned()),
+            anonymous: false,
+            owner_id: "29:owner".to_owned(),
+            owner_name: Some("John".to_owned()),
+            closes_at: None,
+            closed: false,
+            template: None,
+            meeting_id: None,
+            organizer_id: None,
+            attendance_report_id: None,
+            attendance_checked_at: None,
+            created_at: Utc::now(),
+            locale: locale.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_get_reminder_activity() {
+        // Act
+        let result = get_reminder_activity(&card("fr"), &reference("groupChat"));
+
+        // Assert
+        let content = result.attachments.unwrap()[0].content.clone().unwrap();
+        assert_eq!(
+            "John attend votre feedback sur « Rétro ».",
+            content["body"][1]["text"]
+        );
+        assert_eq!(2, content["body"].as_array().unwrap().len());
+        assert_eq!("Action.OpenUrl", content["actions"][0]["type"]);
+    }
+
+    #[test]
+    fn test_get_reminder_activity_in_english() {
+        // Arrange
+        let closes_at = Utc.with_ymd_and_hms(2026, 10, 18, 14, 30, 0).unwrap();
+        let card = FeedbackCard {
+            closes_at: Some(closes_at),
+            ..card("en")
+        };
+
+        // Act
+        let result = get_reminder_activity(&card, &reference("groupChat"));
+
+        // Assert
+        let content = result.attachments.unwrap()[0].content.clone().unwrap();
+        assert_eq!("Your opinion matters!", content["body"][0]["text"]);
+        assert_eq!(
+            "John is waiting for your feedback on “Rétro”.",
+            content["body"][1]["text"]
+        );
+        assert_eq!(
+            "Closes on {{DATE(2026-10-18T14:30:00Z, SHORT)}} at {{TIME(2026-10-18T14:30:00Z)}}",
+            content["body"][2]["text"]
+        );
+        assert_eq!("Answer", content["actions"][0]["title"]);
+    }
+}
@@ -0,0 +1,300 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use crate::{
+    database::queries,
+    error::{Error, Result},
+    i18n::Locale,
+    models::{activity::Activity, ConversationReference},
+    services::teams_client::TeamsClient,
+};
+
+use super::{
+    registry::{self, CommandSpec},
+    router::{CommandHandler, Context},
+    send_adaptive_card, Commands,
+};
+
+pub struct HelpCommand;
+
+#[async_trait]
+impl CommandHandler for HelpCommand {
+    async fn handle(&self, context: &Context, command: Commands) -> Result<()> {
+        let Commands::Help(spec) = command else {
+            return Err(Error::UnknownCommand(command.spec().name.to_owned()));
+        };
+
+        send_help_card(
+            &context.state.teams_client,
+            &context.state.pool,
+            &context.activity,
+            spec,
+        )
+        .await
+    }
+}
+
+pub async fn send_help_card(
+    client: &TeamsClient,
+    pool: &PgPool,
+    activity: &Activity,
+    command: Option<&CommandSpec>,
+) -> Result<()> {
+    let locale = Locale::from(activity);
+    let card = match command {
+        Some(spec) => get_command_help_adaptive_card(spec, locale),
+        None => get_help_adaptive_card(&registry::all().collect::<Vec<_>>(), locale),
+    };
+
+    let response = send_adaptive_card(client, activity, &card).await?;
+
+    queries::conversation_reference_query::save_reference(
+        &ConversationReference {
+            activity_id: Some(response.id),
+            ..activity.get_conversation_reference()
+        },
+        pool,
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn get_help_adaptive_card(commands: &[&CommandSpec], locale: Locale) -> serde_json::Value {
+    let commands: Vec<_> = commands
+        .iter()
+        .map(|spec| {
+            serde_json::json!({
+                "type": "Container",
+                "separator": true,
+                "items": [
+                    {
+                        "type": "TextBlock",
+                        "text": format!("`{}`", spec.syntax.get(locale)),
+                        "weight": "Bolder",
+                        "wrap": true
+                    },
+                    {
+                        "type": "TextBlock",
+                        "text": spec.description.get(locale),
+                        "wrap": true,
+                        "spacing": "Small"
+                    }
+                ]
+            })
+        })
+        .collect();
+    let (title, hint) = match locale {
+        Locale::French => (
+            "Commandes disponibles",
+            "Tapez `help <commande>` pour en savoir plus sur une commande.",
+        ),
+        Locale::English => (
+            "Available commands",
+            "Type `help <command>` to learn more about a command.",
+        ),
+    };
+
+    serde_json::json!({
+        "type": "AdaptiveCard",
+        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+        "version": "1.5",
+        "body": [
+            {
+                "type": "TextBlock",
+                "text": title,
+                "wrap": true,
+                "style": "heading"
+            },
+            {
+                "type": "Container",
+                "items": commands
+            },
+            {
+                "type": "TextBlock",
+                "text": hint,
+                "wrap": true,
+                "isSubtle": true,
+                "separator": true,
+                "spacing": "Large"
+            }
+        ]
+    })
+}
+
+fn get_command_help_adaptive_card(spec: &CommandSpec, locale: Locale) -> serde_json::Value {
+    let (syntax, aliases, subcommands_title, examples_title) = match locale {
+        Locale::French => ("Syntaxe", "Alias", "Sous-commandes", "Exemples"),
+        Locale::English => ("Syntax", "Aliases", "Subcommands", "Examples"),
+    };
+
+    let mut facts = vec![serde_json::json!({
+        "title": syntax,
+        "value": format!("`{}`", spec.syntax.get(locale))
+    })];
+    if !spec.aliases.is_empty() {
+        facts.push(serde_json::json!({
+            "title": aliases,
+            "value": spec.aliases.join(", ")
+        }));
+    }
+
+    let options: Vec<_> = spec
+        .options
+        .iter()
+        .map(|option| {
+            serde_json::json!({
+                "title": format!("`{}`", option.syntax(locale)),
+                "value": option.description.get(locale)
+            })
+        })
+        .collect();
+
+    let subcommands: Vec<_> = spec
+        .subcommands
+        .iter()
+        .map(|subcommand| {
+            serde_json::json!({
+                "title": format!("`{}`", subcommand.syntax.get(locale)),
+                "value": subcommand.description.get(locale)
+            })
+        })
+        .collect();
+
+    let examples: Vec<_> = spec
+        .examples
+        .iter()
+        .map(|example| {
+            serde_json::json!({
+                "type": "TextBlock",
+                "text": format!("`{example}`"),
+                "wrap": true,
+                "spacing": "Small"
+            })
+        })
+        .collect();
+
+    let mut body = vec![
+        serde_json::json!({
+            "type": "TextBlock",
+            "text": spec.name,
+            "wrap": true,
+            "style": "heading"
+        }),
+        serde_json::json!({
+            "type": "TextBlock",
+            "text": spec.description.get(locale),
+            "wrap": true
+        }),
+        serde_json::json!({
+            "type": "FactSet",
+            "facts": facts
+        }),
+    ];
+    for (title, facts) in [("Options", options), (subcommands_title, subcommands)] {
+        if !facts.is_empty() {
+            body.push(get_section_title(title));
+            body.push(serde_json::json!({
+                "type": "FactSet",
+                "facts": facts
+            }));
+        }
+    }
+    body.push(get_section_title(examples_title));
+    body.push(serde_json::json!({
+        "type": "Container",
+        "items": examples
+    }));
+
+    serde_json::json!({
+        "type": "AdaptiveCard",
+        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+        "version": "1.5",
+        "body": body
+    })
+}
+
+fn get_section_title(title: &str) -> serde_json::Value {
+    serde_json::json!({
+        "type": "TextBlock",
+        "text": title,
+        "wrap": true,
+        "weight": "Bolder",
+        "separator": true,
+        "spacing": "Large"
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_help_adaptive_card() {
+        // Arrange
+        let commands: Vec<_> = registry::all().collect();
+
+        // Act
+        let result = get_help_adaptive_card(&commands, Locale::French);
+
+        // Assert
+        let items = result["body"][1]["items"].as_array().unwrap();
+        assert_eq!(6, items.len());
+        for (spec, item) in commands.iter().zip(items) {
+            assert_eq!(
+                format!("`{}`", spec.syntax.french),
+                item["items"][0]["text"]
+            );
+            assert_eq!(spec.description.french, item["items"][1]["text"]);
+        }
+    }
+
+    #[test]
+    fn test_get_command_help_adaptive_card() {
+        // Act
+        let result = get_command_help_adaptive_card(&registry::HELP, Locale::French);
+
+        // Assert
+        assert_eq!("help", result["body"][0]["text"]);
+        assert_eq!("aide, ?", result["body"][2]["facts"][1]["value"]);
+        assert_eq!(
+            registry::HELP.examples.len(),
+            result["body"][4]["items"].as_array().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_get_command_help_adaptive_card_with_options() {
+        // Act
+        let result = get_command_help_adaptive_card(&registry::FEEDBACK, Locale::French);
+
+        // Assert
+        assert_eq!("Options", result["body"][3]["text"]);
+        assert_eq!("`--close-in durée`", result["body"][4]["facts"][1]["title"]);
+        assert_eq!("Sous-commandes", result["body"][5]["text"]);
+        assert_eq!(
+            registry::FEEDBACK.subcommands.len(),
+            result["body"][6]["facts"].as_array().unwrap().len()
+        );
+        assert_eq!("Exemples", result["body"][7]["text"]);
+    }
+
+    #[test]
+    fn test_get_command_help_adaptive_card_in_english() {
+        // Act
+        let result = get_command_help_adaptive_card(&registry::FEEDBACK, Locale::English);
+
+        // Assert
+        assert_eq!(
+            registry::FEEDBACK.description.english,
+            result["body"][1]["text"]
+        );
+        assert_eq!("Syntax", result["body"][2]["facts"][0]["title"]);
+        assert_eq!(
+            "`--close-in duration`",
+            result["body"][4]["facts"][1]["title"]
+        );
+        assert_eq!("Subcommands", result["body"][5]["text"]);
+        assert_eq!("Examples", result["body"][7]["text"]);
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use sqlx::{Acquire, PgPool};
+use tracing::warn;
+
+use crate::{
+    database::queries::{
+        self,
+        attendance_query::{AttendanceReport, NewAttendance, NewAttendanceReport},
+    },
+    error::Result,
+    services::graph_client::{AttendanceRecord, GraphClient, TeamworkOnlineMeetingInfo},
+};
+
+/// How long after the end of a session, in seconds, a feedback can still be about it.
+const MAX_FEEDBACK_DELAY: i64 = 2 * 60 * 60;
+
+/// How long, in seconds, the attendance reports are not fetched again after the session of a feedback was not found in them.
+const ATTENDANCE_CHECK_INTERVAL: i64 = 5 * 60;
+
+/// The meeting behind a meeting chat, as far as Graph tells.
+#[derive(Debug, Default)]
+pub(super) struct Meeting {
+    /// The id of the online meeting, missing when only its calendar event was found.
+    pub id: Option<String>,
+    pub subject: Option<String>,
+    pub start: Option<DateTime<Utc>>,
+    /// The Entra ID object id of the organizer.
+    pub organizer_id: String,
+    pub organizer_name: Option<String>,
+}
+
+impl Meeting {
+    /// The title of a feedback about the meeting, its date being rendered in the time zone of the user.
+    pub fn get_title(&self) -> Option<String> {
+        let subject = self.subject.as_deref().filter(|x| !x.trim().is_empty())?;
+
+        Some(match self.start {
+            Some(start) => {
+                let start = start.to_rfc3339_opts(SecondsFormat::Secs, true);
+                format!("{subject} du {{{{DATE({start}, SHORT)}}}}")
+            }
+            None => subject.to_owned(),
+        })
+    }
+}
+
+/// Gets the meeting of a chat from its online meeting, or from its calendar event when the online meeting cannot be read, e.g. without an application access policy for the organizer.
+#[tracing::instrument(skip_all)]
+pub(super) async fn get_meeting(
+    client: &GraphClient,
+    info: &TeamworkOnlineMeetingInfo,
+) -> Result<Option<Meeting>> {
+    let Some(organizer) = info.organizer.as_ref() else {
+        return Ok(None);
+    };
+    let Some(organizer_id) = organizer.id.as_deref() else {
+        return Ok(None);
+    };
+
+    if let Some(ref join_web_url) = info.join_web_url {
+        match client.get_online_meeting(organizer_id, join_web_url).await {
+            Ok(Some(meeting)) => {
+                let organizer_name = meeting
+                    .participants
+                    .organizer
+                    .and_then(|x| x.identity)
+                    .and_then(|x| x.user)
+                    .and_then(|x| x.display_name)
+                    .or_else(|| organizer.display_name.clone());
+
+                return Ok(Some(Meeting {
+                    id: Some(meeting.id),
+                    subject: meeting.subject,
+                    start: meeting.start_date_time,
+                    organizer_id: organizer_id.to_owned(),
+                    organizer_name,
+                }));
+            }
+            Ok(None) => {}
+            Err(e) => warn!(
+                "An error occured while fetching the online meeting : {:?}",
+                e
+            ),
+        }
+    }
+
+    let Some(ref calendar_event_id) = info.calendar_event_id else {
+        return Ok(None);
+    };
+    let event = client.get_event(organizer_id, calendar_event_id).await?;
+
+    Ok(Some(Meeting {
+        id: None,
+        subject: event.subject,
+        start: event.start.and_then(|x| x.to_utc()),
+        organizer_id: organizer_id.to_owned(),
+        organizer_name: organizer.display_name.clone().or_else(|| {
+            event
+                .organizer
+                .and_then(|x| x.email_address)
+                .and_then(|x| x.name)
+        }),
+    }))
+}
+
+/// What is known of the meeting a feedback was requested in.
+pub(super) struct FeedbackMeeting<'a> {
+    /// The id of the card of the feedback.
+    pub feedback_id: &'a str,
+    pub meeting_id: Option<&'a str>,
+    pub organizer_id: Option<&'a str>,
+    pub created_at: DateTime<Utc>,
+    /// The session the feedback is about, once found.
+    pub attendance_report_id: Option<&'a str>,
+    /// When the session was last looked for without being found.
+    pub attendance_checked_at: Option<DateTime<Utc>>,
+}
+
+/// The Entra ID object ids of the users who attended the session of the meeting a feedback is about, or `None` when it was not requested in a meeting or the session is not over yet. The attendance is only used to target the feedback, so a failure is logged and `None` returned.
+pub(super) async fn get_attendee_ids(
+    client: &GraphClient,
+    pool: &PgPool,
+    feedback: &FeedbackMeeting<'_>,
+) -> Option<HashSet<String>> {
+    match find_attendee_ids(client, pool, feedback).await {
+        Ok(attendee_ids) => attendee_ids,
+        Err(e) => {
+            warn!(
+                "An error occured while fetching the attendance of the feedback {} : {:?}",
+                feedback.feedback_id, e
+            );
+            None
+        }
+    }
+}
+
+async fn find_attendee_ids(
+    client: &GraphClient,
+    pool: &PgPool,
+    feedback: &FeedbackMeeting<'_>,
+) -> Result<Option<HashSet<String>>> {
+    let attendee_ids = match feedback.attendance_report_id {
+        Some(report_id) => queries::attendance_query::get_attendee_ids(report_id, pool).await?,
+        None => {
+            let (Some(meeting_id), Some(organizer_id)) =
+                (feedback.meeting_id, feedback.organizer_id)
+            else {
+                return Ok(None);
+            };
+            if !is_check_due(feedback.attendance_checked_at, Utc::now()) {
+                return Ok(None);
+            }
+
+            sync_attendance(client, pool, meeting_id, organizer_id).await?;
+
+            let reports = queries::attendance_query::get_reports(meeting_id, pool).await?;
+            let Some(report) = select_report(&reports, feedback.created_at) else {
+                queries::feedback_query::set_attendance_checked(feedback.feedback_id, pool).await?;
+                return Ok(None);
+            };
+
+            let attendee_ids =
+                queries::attendance_query::get_attendee_ids(&report.id, pool).await?;
+            queries::feedback_query::set_attendance_report(
+                feedback.feedback_id,
+                &report.id,
+                i32::try_from(attendee_ids.len()).unwrap_or(i32::MAX),
+                pool,
+            )
+            .await?;
+
+            attendee_ids
+        }
+    };
+
+    Ok(Some(attendee_ids.into_iter().collect()))
+}
+
+/// Whether the attendance reports can be fetched again, the session not being found in them at `checked_at`.
+fn is_check_due(checked_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    checked_at.is_none_or(|x| x + chrono::Duration::seconds(ATTENDANCE_CHECK_INTERVAL) <= now)
+}
+
+/// Saves the attendance reports of the meeting which were not saved yet, a report never changing once available.
+#[tracing::instrument(skip(client, pool))]
+async fn sync_attendance(
+    client: &GraphClient,
+    pool: &PgPool,
+    meeting_id: &str,
+    organizer_id: &str,
+) -> Result<()> {
+    let saved: HashSet<_> = queries::attendance_query::get_reports(meeting_id, pool)
+        .await?
+        .into_iter()
+        .map(|x| x.id)
+        .collect();
+    let reports = client
+        .get_attendance_reports(organizer_id, meeting_id)
+        .await?;
+
+    for report in reports.iter().filter(|x| !saved.contains(&x.id)) {
+        let records = client
+            .get_attendance_records(organizer_id, meeting_id, &report.id)
+            .await?;
+
+        let mut conn = pool.acquire().await?;
+        let mut tx = conn.begin().await?;
+
+        queries::attendance_query::create_report(
+            &NewAttendanceReport {
+                id: &report.id,
+                meeting_id,
+                started_at: report.meeting_start_date_time,
+                ended_at: report.meeting_end_date_time,
+            },
+            &mut *tx,
+        )
+        .await?;
+
+        for attendance in records.iter().filter_map(get_attendance) {
+            queries::attendance_query::add_attendance(&report.id, &attendance, &mut *tx).await?;
+        }
+
+        tx.commit().await?;
+    }
+
+    Ok(())
+}
+
+/// The session a feedback created at `created_at` is about: the latest one which started before it, and was still going on or ended shortly before. A recurring meeting, or a reused meeting chat, having many sessions, the older ones are never picked.
+fn select_report(
+    reports: &[AttendanceReport],
+    created_at: DateTime<Utc>,
+) -> Option<&AttendanceReport> {
+    reports
+        .iter()
+        .filter(|x| x.started_at.is_some_and(|x| x <= created_at))
+        .filter(|x| {
+            x.ended_at
+                .is_none_or(|x| x + chrono::Duration::seconds(MAX_FEEDBACK_DELAY) >= created_at)
+        })
+        .max_by_key(|x| x.started_at)
+}
+
+/// The attendance of a record, from its first join to its last leave. Anonymous and phone attendees have no Entra ID identity, so they are left out.
+fn get_attendance(record: &AttendanceRecord) -> Option<NewAttendance<'_>> {
+    let identity = record.identity.as_ref()?;
+
+    Some(NewAttendance {
+        user_id: identity.id.as_deref().filter(|x| !x.is_empty())?,
+        name: identity.display_name.as_deref(),
+        email: record.email_address.as_deref(),
+        role: record.role.as_deref(),
+        joined_at: record
+            .attendance_intervals
+            .iter()
+            .map(|x| x.join_date_time)
+            .min(),
+        left_at: record
+            .attendance_intervals
+            .iter()
+            .filter_map(|x| x.leave_date_time)
+            .max(),
+        duration: i32::try_from(record.total_attendance_in_seconds).unwrap_or(i32::MAX),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        Some("Sprint review"),
+        true,
+        Some("Sprint review du {{DATE(2026-10-18T09:00:00Z, SHORT)}}")
+    )]
+    #[case(Some("Sprint review"), false, Some("Sprint review"))]
+    #[case(Some("  "), true, None)]
+    #[case(None, true, None)]
+    fn test_get_title(
+        #[case] subject: Option<&str>,
+        #[case] has_start: bool,
+        #[case] expected: Option<&str>,
+    ) {
+        // Arrange
+        let meeting = Meeting {
+            subject: subject.map(str::to_owned),
+            start: has_start.then(|| Utc.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap()),
+            ..Default::default()
+        };
+
+        // Act
+        let result = meeting.get_title();
+
+        // Assert
+        assert_eq!(expected, result.as_deref());
+    }
+
+    #[test]
+    fn test_get_attendance() {
+        // Arrange
+        let record: AttendanceRecord = serde_json::from_value(serde_json::json!({
+            "emailAddress": "john.doe@contoso.com",
+            "role": "Attendee",
+            "identity": { "id": "8c1d2e3f", "displayName": "John Doe" },
+            "totalAttendanceInSeconds": 2400,
+            "attendanceIntervals": [
+                {
+                    "joinDateTime": "2026-10-18T09:40:00Z",
+                    "leaveDateTime": "2026-10-18T09:50:00Z",
+                    "durationInSeconds": 600
+                },
+                {
+                    "joinDateTime": "2026-10-18T09:00:00Z",
+                    "leaveDateTime": "2026-10-18T09:30:00Z",
+                    "durationInSeconds": 1800
+                }
+            ]
+        }))
+        .unwrap();
+
+        // Act
+        let result = get_attendance(&record).unwrap();
+
+        // Assert
+        assert_eq!("8c1d2e3f", result.user_id);
+        assert_eq!(Some("John Doe"), result.name);
+        assert_eq!(
+            Some(Utc.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap()),
+            result.joined_at
+        );
+        assert_eq!(
+            Some(Utc.with_ymd_and_hms(2026, 10, 18, 9, 50, 0).unwrap()),
+            result.left_at
+        );
+        assert_eq!(2400, result.duration);
+    }
+
+    #[rstest]
+    #[case(serde_json::json!({ "identity": null }))]
+    #[case(serde_json::json!({ "identity": { "id": "", "displayName": "+33 1 23 45 67 89" } }))]
+    fn test_get_attendance_without_identity(#[case] value: serde_json::Value) {
+        // Arrange
+        let record: AttendanceRecord = serde_json::from_value(value).unwrap();
+
+        // Act
+        let result = get_attendance(&record);
+
+        // Assert
+        assert!(result.is_none());
+    }
+
+    #[rstest]
+    #[case((9, 30), Some("1"))]
+    #[case((10, 15), Some("1"))]
+    #[case((14, 30), Some("2"))]
+    #[case((16, 0), Some("2"))]
+    #[case((12, 30), None)]
+    #[case((8, 0), None)]
+    fn test_select_report(#[case] created_at: (u32, u32), #[case] expected: Option<&str>) {
+        // Arrange
+        let at = |h, m| Some(Utc.with_ymd_and_hms(2026, 10, 18, h, m, 0).unwrap());
+        let reports = [
+            AttendanceReport {
+                id: "2".to_owned(),
+                started_at: at(14, 0),
+                ended_at: at(15, 0),
+            },
+            AttendanceReport {
+                id: "1".to_owned(),
+                started_at: at(9, 0),
+                ended_at: at(10, 0),
+            },
+        ];
+        let (h, m) = created_at;
+
+        // Act
+        let result = select_report(&reports, at(h, m).unwrap());
+
+        // Assert
+        assert_eq!(expected, result.map(|x| x.id.as_str()));
+    }
+
+    #[rstest]
+    #[case(None, true)]
+    #[case(Some((14, 0)), true)]
+    #[case(Some((14, 58)), false)]
+    fn test_is_check_due(#[case] checked_at: Option<(u32, u32)>, #[case] expected: bool) {
+        // Arrange
+        let at = |(h, m)| Utc.with_ymd_and_hms(2026, 10, 18, h, m, 0).unwrap();
+
+        // Act
+        let result = is_check_due(checked_at.map(at), at((15, 0)));
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+}
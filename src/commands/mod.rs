@@ -1,42 +1,224 @@
+pub mod args;
 pub mod feedback_command;
+pub mod feedback_export;
+pub mod feedback_form;
+pub mod feedback_history;
+pub mod feedback_reminder;
+pub mod help_command;
+mod meeting;
+pub mod registry;
+pub mod router;
+pub mod templates;
+
+use std::time::Duration;
+
+use tracing::warn;
 
 use crate::{
     error::{Error, Result},
+    i18n::Locale,
     models::{
         activity::{Activity, Type},
         attachment::ContentType,
-        Attachment, ResourceResponse,
+        Action, Attachment, ResourceResponse,
     },
+    scheduler::{Job, Scheduler},
     services::teams_client::TeamsClient,
 };
 
+use self::{
+    args::Arguments,
+    feedback_command::{
+        FeedbackCommand, FeedbackDeadline, FeedbackDeadlineJob, FeedbackEntryAction,
+        FeedbackRefreshAction,
+    },
+    feedback_export::ExportArgs,
+    feedback_form::FeedbackFormAction,
+    feedback_history::HistoryArgs,
+    feedback_reminder::{FeedbackReminder, FeedbackReminderJob},
+    help_command::HelpCommand,
+    registry::{
+        CommandSpec, FEEDBACK, FEEDBACK_CLOSE, FEEDBACK_EXPORT, FEEDBACK_HISTORY, FEEDBACK_REMIND,
+        HELP,
+    },
+    router::CommandRouter,
+    templates::Template,
+};
+
 #[derive(Debug, PartialEq)]
 pub enum Commands {
-    Feedback,
-    Help,
+    Feedback(FeedbackArgs),
+    /// Closes the open feedback requests of the user in the conversation.
+    FeedbackClose,
+    /// Sends the answers to the feedbacks of the user in the conversation as a file.
+    FeedbackExport(ExportArgs),
+    /// Shows the trend of the last feedbacks of the conversation.
+    FeedbackHistory(HistoryArgs),
+    /// Reminds the members who did not answer the open feedbacks of the user in the conversation.
+    FeedbackRemind,
+    /// Shows the help of a single command, or of all of them when missing.
+    Help(Option<&'static CommandSpec>),
+}
+
+impl Commands {
+    pub fn spec(&self) -> &'static CommandSpec {
+        match self {
+            Commands::Feedback(_) => &FEEDBACK,
+            Commands::FeedbackClose => &FEEDBACK_CLOSE,
+            Commands::FeedbackExport(_) => &FEEDBACK_EXPORT,
+            Commands::FeedbackHistory(_) => &FEEDBACK_HISTORY,
+            Commands::FeedbackRemind => &FEEDBACK_REMIND,
+            Commands::Help(_) => &HELP,
+        }
+    }
+
+    fn parse_feedback(arguments: &mut Arguments) -> Result<Commands> {
+        Ok(Self::Feedback(FeedbackArgs {
+            anonymous: arguments.flag("anonymous"),
+            close_in: arguments.option("close-in")?,
+            remind_in: arguments.option("remind-in")?,
+            template: arguments.option("template")?,
+            title: arguments.positional("title")?,
+        }))
+    }
+
+    fn parse_feedback_close(_: &mut Arguments) -> Result<Commands> {
+        Ok(Self::FeedbackClose)
+    }
+
+    fn parse_feedback_export(arguments: &mut Arguments) -> Result<Commands> {
+        Ok(Self::FeedbackExport(ExportArgs {
+            format: arguments.option("format")?.unwrap_or_default(),
+            names: arguments.flag("names"),
+        }))
+    }
+
+    fn parse_feedback_history(arguments: &mut Arguments) -> Result<Commands> {
+        Ok(Self::FeedbackHistory(HistoryArgs {
+            last: arguments.option("last")?,
+        }))
+    }
+
+    fn parse_feedback_remind(_: &mut Arguments) -> Result<Commands> {
+        Ok(Self::FeedbackRemind)
+    }
+
+    /// Parses the arguments of `help`, the command to describe if any.
+    fn parse_help(arguments: &mut Arguments) -> Result<Commands> {
+        let command = match arguments.positional::<String>("command")? {
+            Some(command) => Self::Help(Some(
+                registry::resolve(&command, arguments).ok_or(Error::UnknownCommand(command))?,
+            )),
+            None => Self::Help(None),
+        };
+
+        Ok(command)
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct FeedbackArgs {
+    /// Title of the feedback request, the name of the conversation is used when missing.
+    pub title: Option<String>,
+    /// Whether the answers are stored without being linked to the respondents.
+    pub anonymous: bool,
+    /// Delay after which the answers are rejected, if any.
+    pub close_in: Option<Duration>,
+    /// Delay after which the members who did not answer are reminded, if any.
+    pub remind_in: Option<Duration>,
+    /// Questions asked instead of the single rating, if any.
+    pub template: Option<&'static Template>,
 }
 
 impl TryFrom<&str> for Commands {
     type Error = Error;
 
+    /// Parses a whole command line, e.g. `feedback "Sprint review"`.
     fn try_from(value: &str) -> Result<Commands> {
-        match value.trim() {
-            "feedback" => Ok(Self::Feedback),
-            "help" => Ok(Self::Help),
-            _ => Err(Error::UnknownCommand(value.to_owned())),
+        let mut tokens = args::tokenize(value)?.into_iter();
+        let name = tokens.next().map(|x| x.value).unwrap_or_default();
+        let mut arguments = Arguments::new(tokens.collect());
+
+        let spec = registry::resolve(&name, &mut arguments).ok_or(Error::UnknownCommand(name))?;
+
+        let command = (spec.parse)(&mut arguments)?;
+
+        arguments.finish()?;
+
+        Ok(command)
+    }
+}
+
+/// Registers the handlers of every command and card action of the bot.
+pub fn router() -> CommandRouter {
+    CommandRouter::new()
+        .command(&FEEDBACK, FeedbackCommand)
+        .command(&FEEDBACK_CLOSE, FeedbackCommand)
+        .command(&FEEDBACK_EXPORT, FeedbackCommand)
+        .command(&FEEDBACK_HISTORY, FeedbackCommand)
+        .command(&FEEDBACK_REMIND, FeedbackCommand)
+        .command(&HELP, HelpCommand)
+        .action(Action::FEEDBACK, FeedbackEntryAction)
+        .action(Action::FEEDBACK_REFRESH, FeedbackRefreshAction)
+        .action(Action::FEEDBACK_FORM, FeedbackFormAction)
+}
+
+/// Registers the handlers of every job the commands can enqueue.
+pub fn scheduler() -> Scheduler {
+    Scheduler::new()
+        .job(FeedbackDeadline::KIND, FeedbackDeadlineJob)
+        .job(FeedbackReminder::KIND, FeedbackReminderJob)
+}
+
+/// Sends the response to the conversation of the activity, in its thread for the channels.
+#[tracing::instrument(skip_all)]
+pub async fn send_response(
+    client: &TeamsClient,
+    activity: &Activity,
+    response: &Activity,
+) -> Result<ResourceResponse> {
+    let base_url = activity.service_url.as_deref();
+    let conversation_id = &activity.conversation.id;
+
+    match activity.get_thread_id() {
+        Some(activity_id) => {
+            let response = Activity {
+                reply_to_id: Some(activity_id.to_owned()),
+                ..response.clone()
+            };
+
+            client
+                .reply_to_activity(base_url, conversation_id, activity_id, &response)
+                .await
         }
+        None => {
+            client
+                .send_to_conversation(base_url, conversation_id, response)
+                .await
+        }
+    }
+}
+
+/// Shows that the bot is working on the activity, before a slow command. The indicator is only cosmetic, so a failure is logged and ignored.
+pub async fn send_typing(client: &TeamsClient, activity: &Activity) {
+    if let Err(e) = client
+        .send_typing(activity.service_url.as_deref(), &activity.conversation.id)
+        .await
+    {
+        warn!(
+            "An error occured while sending the typing indicator : {:?}",
+            e
+        );
     }
 }
 
 #[tracing::instrument(skip_all)]
 pub async fn send_message(client: &TeamsClient, activity: &Activity, message: &str) -> Result<()> {
-    let (base_url, mut response) = activity.create_response();
+    let (_, mut response) = activity.create_response();
     response.r#type = Type::Message;
     response.text = Some(message.to_owned());
 
-    client
-        .send_to_conversation(base_url, &activity.conversation.id, &response)
-        .await?;
+    send_response(client, activity, &response).await?;
 
     Ok(())
 }
@@ -47,16 +229,41 @@ pub async fn send_adaptive_card(
     activity: &Activity,
     adaptive_card: &serde_json::Value,
 ) -> Result<ResourceResponse> {
-    let (base_url, mut response) = activity.create_response();
+    let (_, mut response) = activity.create_response();
     response.r#type = Type::Message;
     response.attachments = Some(vec![Attachment {
         content: Some(adaptive_card.to_owned()),
         content_type: Some(ContentType::Adaptive),
+        ..Default::default()
     }]);
 
-    let result = client
-        .send_to_conversation(base_url, &activity.conversation.id, &response)
-        .await?;
+    send_response(client, activity, &response).await
+}
+
+/// The card explaining to the user that their request failed.
+pub fn get_error_adaptive_card(message: &str, locale: Locale) -> serde_json::Value {
+    let title = match locale {
+        Locale::French => "Oups !",
+        Locale::English => "Oops!",
+    };
 
-    Ok(result)
+    serde_json::json!({
+        "type": "AdaptiveCard",
+        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+        "version": "1.5",
+        "body": [
+            {
+                "type": "TextBlock",
+                "text": title,
+                "wrap": true,
+                "style": "heading",
+                "color": "Attention"
+            },
+            {
+                "type": "TextBlock",
+                "text": message,
+                "wrap": true
+            }
+        ]
+    })
 }
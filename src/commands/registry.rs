@@ -0,0 +1,384 @@
+use crate::{
+    error::Result,
+    i18n::{Locale, Text},
+};
+
+use super::{args::Arguments, Commands};
+
+/// Describes a command, used to resolve the command name, parse its arguments and render the help.
+#[derive(Debug)]
+pub struct CommandSpec {
+    /// The full name of the command, e.g. `feedback close` for a subcommand.
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub syntax: Text,
+    pub description: Text,
+    pub options: &'static [OptionSpec],
+    pub examples: &'static [&'static str],
+    /// The commands selected by a word following the name of this one.
+    pub subcommands: &'static [&'static CommandSpec],
+    /// Reads the arguments following the name of the command.
+    pub parse: fn(&mut Arguments) -> Result<Commands>,
+}
+
+/// The commands are identified by their name, which is unique.
+impl PartialEq for CommandSpec {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl CommandSpec {
+    /// The word selecting the command after the name of its parent, or its name for a top level command.
+    pub fn keyword(&self) -> &'static str {
+        self.name.rsplit(' ').next().unwrap_or(self.name)
+    }
+}
+
+/// Describes a flag, or an option when it takes a value.
+#[derive(Debug)]
+pub struct OptionSpec {
+    pub name: &'static str,
+    /// The placeholder of the value, for an option.
+    pub value: Option<Text>,
+    pub description: Text,
+}
+
+impl OptionSpec {
+    pub fn syntax(&self, locale: Locale) -> String {
+        match self.value {
+            Some(ref value) => format!("--{} {}", self.name, value.get(locale)),
+            None => format!("--{}", self.name),
+        }
+    }
+}
+
+pub const FEEDBACK: CommandSpec = CommandSpec {
+    name: "feedback",
+    aliases: &["avis"],
+    syntax: Text {
+        french: "feedback [\"titre\"] [--anonymous] [--close-in durée] [--remind-in durée] [--template retro|meeting]",
+        english: "feedback [\"title\"] [--anonymous] [--close-in duration] [--remind-in duration] [--template retro|meeting]",
+    },
+    description: Text {
+        french: "Envoie une demande de feedback dans la conversation. Chaque participant peut noter le meeting de 1 à 5 étoiles et laisser un commentaire, le rapport vous est envoyé en privé.",
+        english: "Sends a feedback request to the conversation. Every participant can rate the meeting from 1 to 5 stars and leave a comment, the report is sent to you in a private chat.",
+    },
+    options: &[
+        OptionSpec {
+            name: "anonymous",
+            value: None,
+            description: Text {
+                french: "Les réponses ne sont pas liées à leurs auteurs.",
+                english: "The answers are not linked to their authors.",
+            },
+        },
+        OptionSpec {
+            name: "close-in",
+            value: Some(Text {
+                french: "durée",
+                english: "duration",
+            }),
+            description: Text {
+                french: "Les réponses ne sont plus acceptées passé ce délai.",
+                english: "The answers are no longer accepted after this delay.",
+            },
+        },
+        OptionSpec {
+            name: "remind-in",
+            value: Some(Text {
+                french: "durée",
+                english: "duration",
+            }),
+            description: Text {
+                french: "Les participants qui n'ont pas répondu reçoivent un rappel en privé passé ce délai.",
+                english: "The participants who did not answer get a private reminder after this delay.",
+            },
+        },
+        OptionSpec {
+            name: "template",
+            value: Some(Text {
+                french: "retro|meeting",
+                english: "retro|meeting",
+            }),
+            description: Text {
+                french: "Plusieurs questions sont posées au lieu de la seule note.",
+                english: "Several questions are asked instead of the single rating.",
+            },
+        },
+    ],
+    examples: &[
+        "feedback",
+        "feedback \"Sprint review\"",
+        "feedback \"Rétro\" --anonymous",
+        "feedback --close-in 2h",
+        "feedback \"Rétro\" --template retro",
+        "feedback --close-in 1d --remind-in 2h",
+    ],
+    subcommands: &[
+        &FEEDBACK_CLOSE,
+        &FEEDBACK_REMIND,
+        &FEEDBACK_EXPORT,
+        &FEEDBACK_HISTORY,
+    ],
+    parse: Commands::parse_feedback,
+};
+
+pub const FEEDBACK_CLOSE: CommandSpec = CommandSpec {
+    name: "feedback close",
+    aliases: &[],
+    syntax: Text {
+        french: "feedback close",
+        english: "feedback close",
+    },
+    description: Text {
+        french: "Clôture vos demandes de feedback ouvertes dans la conversation.",
+        english: "Closes your open feedback requests in the conversation.",
+    },
+    options: &[],
+    examples: &["feedback close"],
+    subcommands: &[],
+    parse: Commands::parse_feedback_close,
+};
+
+pub const FEEDBACK_REMIND: CommandSpec = CommandSpec {
+    name: "feedback remind",
+    aliases: &[],
+    syntax: Text {
+        french: "feedback remind",
+        english: "feedback remind",
+    },
+    description: Text {
+        french: "Envoie tout de suite un rappel en privé aux participants qui n'ont pas répondu à vos demandes ouvertes dans la conversation.",
+        english: "Sends a private reminder right away to the participants who did not answer your open requests in the conversation.",
+    },
+    options: &[],
+    examples: &["feedback remind"],
+    subcommands: &[],
+    parse: Commands::parse_feedback_remind,
+};
+
+pub const FEEDBACK_EXPORT: CommandSpec = CommandSpec {
+    name: "feedback export",
+    aliases: &[],
+    syntax: Text {
+        french: "feedback export [--format csv|json] [--names]",
+        english: "feedback export [--format csv|json] [--names]",
+    },
+    description: Text {
+        french:
+            "Vous envoie en privé les réponses à vos demandes de feedback dans la conversation.",
+        english:
+            "Sends you in a private chat the answers to your feedback requests in the conversation.",
+    },
+    options: &[
+        OptionSpec {
+            name: "format",
+            value: Some(Text {
+                french: "csv|json",
+                english: "csv|json",
+            }),
+            description: Text {
+                french: "Le format du fichier, CSV par défaut.",
+                english: "The format of the file, CSV by default.",
+            },
+        },
+        OptionSpec {
+            name: "names",
+            value: None,
+            description: Text {
+                french: "Ajoute le nom des participants, si les réponses ne sont pas anonymes.",
+                english: "Adds the names of the participants, when the answers are not anonymous.",
+            },
+        },
+    ],
+    examples: &["feedback export", "feedback export --format json --names"],
+    subcommands: &[],
+    parse: Commands::parse_feedback_export,
+};
+
+pub const FEEDBACK_HISTORY: CommandSpec = CommandSpec {
+    name: "feedback history",
+    aliases: &[],
+    syntax: Text {
+        french: "feedback history [--last n]",
+        english: "feedback history [--last n]",
+    },
+    description: Text {
+        french: "Affiche l'évolution des dernières sessions de la conversation, comme celles d'un meeting récurrent.",
+        english: "Shows the trend of the last sessions of the conversation, such as the ones of a recurring meeting.",
+    },
+    options: &[OptionSpec {
+        name: "last",
+        value: Some(Text {
+            french: "n",
+            english: "n",
+        }),
+        description: Text {
+            french: "Le nombre de sessions affichées, 5 par défaut.",
+            english: "The number of sessions shown, 5 by default.",
+        },
+    }],
+    examples: &["feedback history", "feedback history --last 10"],
+    subcommands: &[],
+    parse: Commands::parse_feedback_history,
+};
+
+pub const HELP: CommandSpec = CommandSpec {
+    name: "help",
+    aliases: &["aide", "?"],
+    syntax: Text {
+        french: "help [commande]",
+        english: "help [command]",
+    },
+    description: Text {
+        french: "Affiche la liste des commandes, ou le détail d'une commande.",
+        english: "Shows the list of the commands, or the details of a command.",
+    },
+    options: &[],
+    examples: &["help", "help feedback", "help feedback export"],
+    subcommands: &[],
+    parse: Commands::parse_help,
+};
+
+/// Every top level command understood by the bot, in the order they are listed in the help.
+pub const COMMANDS: &[&CommandSpec] = &[&FEEDBACK, &HELP];
+
+/// Every command, each top level one being followed by its subcommands.
+pub fn all() -> impl Iterator<Item = &'static CommandSpec> {
+    COMMANDS
+        .iter()
+        .flat_map(|spec| std::iter::once(*spec).chain(spec.subcommands.iter().copied()))
+}
+
+/// Finds a top level command by its name or one of its aliases, ignoring the case.
+pub fn find(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().copied().find(|spec| {
+        spec.name.eq_ignore_ascii_case(name)
+            || spec.aliases.iter().any(|x| x.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Finds a command by its name, then its subcommand if the next argument selects one.
+pub fn resolve(name: &str, arguments: &mut Arguments) -> Option<&'static CommandSpec> {
+    let spec = find(name)?;
+
+    let subcommand = spec
+        .subcommands
+        .iter()
+        .copied()
+        .find(|x| arguments.subcommand(x.keyword()));
+
+    Some(subcommand.unwrap_or(spec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("feedback", Some(&FEEDBACK))]
+    #[case("Feedback", Some(&FEEDBACK))]
+    #[case("avis", Some(&FEEDBACK))]
+    #[case("help", Some(&HELP))]
+    #[case("?", Some(&HELP))]
+    #[case("unknown", None)]
+    fn test_find(#[case] name: &str, #[case] expected: Option<&CommandSpec>) {
+        // Act
+        let result = find(name);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[rstest]
+    #[case("feedback", "", &FEEDBACK)]
+    #[case("avis", "close", &FEEDBACK_CLOSE)]
+    #[case("feedback", "EXPORT --names", &FEEDBACK_EXPORT)]
+    #[case("feedback", "\"Sprint review\"", &FEEDBACK)]
+    #[case("feedback", "\"Close\"", &FEEDBACK)]
+    #[case("feedback", "\"export\" --anonymous", &FEEDBACK)]
+    #[case("help", "feedback", &HELP)]
+    fn test_resolve(#[case] name: &str, #[case] arguments: &str, #[case] expected: &CommandSpec) {
+        // Arrange
+        let mut arguments = Arguments::new(crate::commands::args::tokenize(arguments).unwrap());
+
+        // Act
+        let result = resolve(name, &mut arguments);
+
+        // Assert
+        assert_eq!(Some(expected), result);
+    }
+
+    #[test]
+    fn test_examples_parse_to_their_command() {
+        for spec in all() {
+            for example in spec.examples {
+                // Act
+                let result = Commands::try_from(*example);
+
+                // Assert
+                assert_eq!(
+                    Some(spec),
+                    result.as_ref().ok().map(Commands::spec),
+                    "{example}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_options_are_parsed() {
+        for spec in all() {
+            for option in spec.options {
+                // Arrange
+                let value = match option.value {
+                    Some(_) => spec.examples.iter().find_map(|x| {
+                        let (_, value) = x.split_once(&format!("--{} ", option.name))?;
+                        value.split(' ').next()
+                    }),
+                    None => Some(""),
+                };
+                let line = format!("{} --{} {}", spec.name, option.name, value.unwrap_or("?"));
+
+                // Act
+                let result = Commands::try_from(line.as_str());
+
+                // Assert
+                assert!(result.is_ok(), "{line}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_subcommand_names() {
+        for spec in COMMANDS {
+            for subcommand in spec.subcommands {
+                // Assert
+                assert_eq!(
+                    format!("{} {}", spec.name, subcommand.keyword()),
+                    subcommand.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_names_are_unique() {
+        // Arrange
+        let mut names: Vec<_> = COMMANDS
+            .iter()
+            .flat_map(|spec| std::iter::once(&spec.name).chain(spec.aliases.iter()))
+            .map(|name| name.to_lowercase())
+            .collect();
+        let count = names.len();
+
+        // Act
+        names.sort();
+        names.dedup();
+
+        // Assert
+        assert_eq!(count, names.len());
+    }
+}
@@ -0,0 +1,205 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{
+    error::{Error, Result},
+    models::{action::CardAction, Activity},
+    state::AppState,
+};
+
+use super::{registry::CommandSpec, Commands};
+
+/// Everything a handler needs to process an incoming activity.
+pub struct Context {
+    pub state: AppState,
+    pub activity: Activity,
+}
+
+impl Context {
+    pub fn new(state: AppState, activity: Activity) -> Self {
+        Self { state, activity }
+    }
+}
+
+/// Handles a parsed command, registered for a [`CommandSpec`] with [`CommandRouter::command`].
+#[async_trait]
+pub trait CommandHandler: Send + Sync {
+    async fn handle(&self, context: &Context, command: Commands) -> Result<()>;
+}
+
+/// What the user sees once an action is handled. It is only returned to Teams for the `Action.Execute`, as an `Action.Submit` has no synchronous response.
+#[derive(Debug)]
+pub enum ActionResponse {
+    Empty,
+    /// Replaces the card the action was executed from, for the user who executed it.
+    Card(serde_json::Value),
+    Message(String),
+}
+
+/// Handles the data submitted from an Adaptive Card, registered for a verb with [`CommandRouter::action`].
+#[async_trait]
+pub trait ActionHandler: Send + Sync {
+    async fn handle(&self, context: &Context, action: CardAction) -> Result<ActionResponse>;
+}
+
+/// Maps the parsed commands and the card submissions to their handlers.
+#[derive(Clone, Default)]
+pub struct CommandRouter {
+    commands: HashMap<&'static str, Arc<dyn CommandHandler>>,
+    actions: HashMap<&'static str, Arc<dyn ActionHandler>>,
+}
+
+impl CommandRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn command(
+        mut self,
+        spec: &'static CommandSpec,
+        handler: impl CommandHandler + 'static,
+    ) -> Self {
+        self.commands.insert(spec.name, Arc::new(handler));
+        self
+    }
+
+    pub fn action(mut self, verb: &'static str, handler: impl ActionHandler + 'static) -> Self {
+        self.actions.insert(verb, Arc::new(handler));
+        self
+    }
+
+    #[tracing::instrument(skip_all, fields(command = command.spec().name))]
+    pub async fn dispatch_command(&self, context: &Context, command: Commands) -> Result<()> {
+        let name = command.spec().name;
+        let handler = self
+            .commands
+            .get(name)
+            .ok_or_else(|| Error::UnknownCommand(name.to_owned()))?;
+
+        handler.handle(context, command).await
+    }
+
+    /// Routes the value submitted from a card to the handler of its verb. Fails with [`Error::UnknownAction`] when the value matches no registered action.
+    #[tracing::instrument(skip_all)]
+    pub async fn dispatch_action(
+        &self,
+        context: &Context,
+        value: &serde_json::Value,
+    ) -> Result<ActionResponse> {
+        let action = serde_json::from_value::<CardAction>(value.clone())
+            .map_err(|_| Error::UnknownAction(value.clone()))?;
+
+        let handler = self
+            .actions
+            .get(action.action.verb())
+            .ok_or_else(|| Error::UnknownAction(value.clone()))?;
+
+        handler.handle(context, action).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::{
+        auth::{ApiKeyAuthenticator, BotAuthenticator, StaticKeySource},
+        commands::{registry, FeedbackArgs},
+        models::Action,
+        scheduler::Scheduler,
+        services::{credentials::ClientSecret, GraphClient, TeamsClient},
+    };
+
+    struct CountingHandler(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl CommandHandler for CountingHandler {
+        async fn handle(&self, _: &Context, _: Commands) -> Result<()> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ActionHandler for CountingHandler {
+        async fn handle(&self, _: &Context, _: CardAction) -> Result<ActionResponse> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(ActionResponse::Empty)
+        }
+    }
+
+    fn context() -> Context {
+        let client = reqwest::Client::new();
+        let state = AppState {
+            teams_client: TeamsClient::new(
+                client.clone(),
+                "id",
+                Arc::new(ClientSecret::new("secret")),
+            ),
+            graph_client: GraphClient::new(
+                client,
+                "id",
+                "tenant",
+                Arc::new(ClientSecret::new("secret")),
+            ),
+            pool: sqlx::PgPool::connect_lazy("postgres://localhost/test").unwrap(),
+            authenticator: BotAuthenticator::new(Arc::new(StaticKeySource::new()), "id"),
+            api_key_authenticator: ApiKeyAuthenticator::new("key"),
+            router: CommandRouter::new(),
+            scheduler: Scheduler::new(),
+            anonymous_secret: Arc::from("secret"),
+        };
+
+        Context::new(state, Activity::default())
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_command() {
+        // Arrange
+        let count = Arc::new(AtomicUsize::new(0));
+        let router = CommandRouter::new().command(&registry::HELP, CountingHandler(count.clone()));
+        let context = context();
+
+        // Act
+        let help = router
+            .dispatch_command(&context, Commands::Help(None))
+            .await;
+        let feedback = router
+            .dispatch_command(&context, Commands::Feedback(FeedbackArgs::default()))
+            .await;
+
+        // Assert
+        assert!(help.is_ok());
+        assert!(matches!(feedback, Err(Error::UnknownCommand(name)) if name == "feedback"));
+        assert_eq!(1, count.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_action() {
+        // Arrange
+        let count = Arc::new(AtomicUsize::new(0));
+        let router = CommandRouter::new().action(Action::FEEDBACK, CountingHandler(count.clone()));
+        let context = context();
+
+        // Act
+        let feedback = router
+            .dispatch_action(
+                &context,
+                &serde_json::json!({ "verb": "feedback", "instanceId": "42", "rating": 4 }),
+            )
+            .await;
+        let unknown = router
+            .dispatch_action(&context, &serde_json::json!({ "foo": "bar" }))
+            .await;
+
+        // Assert
+        assert!(feedback.is_ok());
+        assert!(matches!(unknown, Err(Error::UnknownAction(_))));
+        assert_eq!(1, count.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,212 @@
+use crate::i18n::Text;
+
+use super::args::FromArgument;
+
+/// The kind of answer expected by a question, stored as text with the questions of a feedback.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuestionKind {
+    /// A rating from 1 to 5 stars.
+    Rating,
+    YesNo,
+    /// A single choice among the choices of the question.
+    Choice,
+    Text,
+}
+
+impl QuestionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuestionKind::Rating => "rating",
+            QuestionKind::YesNo => "yesNo",
+            QuestionKind::Choice => "choice",
+            QuestionKind::Text => "text",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "rating" => Some(QuestionKind::Rating),
+            "yesNo" => Some(QuestionKind::YesNo),
+            "choice" => Some(QuestionKind::Choice),
+            "text" => Some(QuestionKind::Text),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Question {
+    pub label: Text,
+    pub kind: QuestionKind,
+    /// The choices of a [`QuestionKind::Choice`] question, empty otherwise.
+    pub choices: &'static [Text],
+}
+
+/// A set of questions which can be asked instead of the single rating. The first question is the overall rating of the meeting, used by the report like the rating of a simple feedback.
+#[derive(Debug, PartialEq)]
+pub struct Template {
+    pub name: &'static str,
+    pub questions: &'static [Question],
+}
+
+pub const RETRO: Template = Template {
+    name: "retro",
+    questions: &[
+        Question {
+            label: Text {
+                french: "Comment s'est passée la rétro ?",
+                english: "How did the retro go?",
+            },
+            kind: QuestionKind::Rating,
+            choices: &[],
+        },
+        Question {
+            label: Text {
+                french: "Les actions de la dernière rétro ont-elles été menées ?",
+                english: "Were the actions of the last retro carried out?",
+            },
+            kind: QuestionKind::YesNo,
+            choices: &[],
+        },
+        Question {
+            label: Text {
+                french: "Qu'avez-vous pensé du rythme ?",
+                english: "What did you think of the pace?",
+            },
+            kind: QuestionKind::Choice,
+            choices: &[
+                Text {
+                    french: "Trop lent",
+                    english: "Too slow",
+                },
+                Text {
+                    french: "Adapté",
+                    english: "Right",
+                },
+                Text {
+                    french: "Trop rapide",
+                    english: "Too fast",
+                },
+            ],
+        },
+        Question {
+            label: Text {
+                french: "Que pourrions-nous améliorer ?",
+                english: "What could we improve?",
+            },
+            kind: QuestionKind::Text,
+            choices: &[],
+        },
+    ],
+};
+
+pub const MEETING: Template = Template {
+    name: "meeting",
+    questions: &[
+        Question {
+            label: Text {
+                french: "Comment s'est passé le meeting ?",
+                english: "How did the meeting go?",
+            },
+            kind: QuestionKind::Rating,
+            choices: &[],
+        },
+        Question {
+            label: Text {
+                french: "L'ordre du jour a-t-il été respecté ?",
+                english: "Was the agenda followed?",
+            },
+            kind: QuestionKind::YesNo,
+            choices: &[],
+        },
+        Question {
+            label: Text {
+                french: "Qu'avez-vous pensé de la durée ?",
+                english: "What did you think of the length?",
+            },
+            kind: QuestionKind::Choice,
+            choices: &[
+                Text {
+                    french: "Trop courte",
+                    english: "Too short",
+                },
+                Text {
+                    french: "Adaptée",
+                    english: "Right",
+                },
+                Text {
+                    french: "Trop longue",
+                    english: "Too long",
+                },
+            ],
+        },
+        Question {
+            label: Text {
+                french: "Un commentaire ?",
+                english: "Any comment?",
+            },
+            kind: QuestionKind::Text,
+            choices: &[],
+        },
+    ],
+};
+
+/// Every template which can be given to the feedback command.
+pub const TEMPLATES: &[&Template] = &[&RETRO, &MEETING];
+
+/// Finds a template by its name, ignoring the case.
+pub fn find(name: &str) -> Option<&'static Template> {
+    TEMPLATES
+        .iter()
+        .copied()
+        .find(|template| template.name.eq_ignore_ascii_case(name))
+}
+
+impl FromArgument for &'static Template {
+    const EXPECTED: &'static str = "a template among `retro` and `meeting`";
+    const EXPECTED_FR: &'static str = "un modèle parmi `retro` et `meeting`";
+
+    fn from_argument(value: &str) -> Option<Self> {
+        find(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("retro", Some(&RETRO))]
+    #[case("Meeting", Some(&MEETING))]
+    #[case("unknown", None)]
+    fn test_find(#[case] name: &str, #[case] expected: Option<&Template>) {
+        // Act
+        let result = find(name);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_templates() {
+        for template in TEMPLATES {
+            // Assert
+            assert!(<&Template>::EXPECTED.contains(&format!("`{}`", template.name)));
+            assert_eq!(
+                Some(QuestionKind::Rating),
+                template.questions.first().map(|x| x.kind)
+            );
+            for question in template.questions {
+                assert_eq!(
+                    question.kind == QuestionKind::Choice,
+                    !question.choices.is_empty()
+                );
+                assert_eq!(
+                    Some(question.kind),
+                    QuestionKind::parse(question.kind.as_str())
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+use crate::error::Result;
+
+pub struct NewAttendanceReport<'a> {
+    pub id: &'a str,
+    pub meeting_id: &'a str,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+/// Saves a report of a session. A report saved meanwhile by a concurrent vote is kept as is.
+pub async fn create_report<'a, E>(report: &NewAttendanceReport<'_>, executor: E) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!(
+        "INSERT INTO attendance_report (id, meeting_id, started_at, ended_at) VALUES ($1, $2, $3, $4) ON CONFLICT (id) DO NOTHING",
+        report.id,
+        report.meeting_id,
+        report.started_at,
+        report.ended_at
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub struct NewAttendance<'a> {
+    /// Entra ID object id of the attendee.
+    pub user_id: &'a str,
+    pub name: Option<&'a str>,
+    pub email: Option<&'a str>,
+    pub role: Option<&'a str>,
+    pub joined_at: Option<DateTime<Utc>>,
+    pub left_at: Option<DateTime<Utc>>,
+    /// Total attendance in seconds.
+    pub duration: i32,
+}
+
+/// Saves the attendance of a user to a session. A user listed twice by the report is only saved once.
+pub async fn add_attendance<'a, E>(
+    report_id: &str,
+    attendance: &NewAttendance<'_>,
+    executor: E,
+) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!(
+        "INSERT INTO attendance (report_id, user_id, name, email, role, joined_at, left_at, duration) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) ON CONFLICT DO NOTHING",
+        report_id,
+        attendance.user_id,
+        attendance.name,
+        attendance.email,
+        attendance.role,
+        attendance.joined_at,
+        attendance.left_at,
+        attendance.duration
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub struct AttendanceReport {
+    pub id: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+pub async fn get_reports<'a, E>(meeting_id: &str, executor: E) -> Result<Vec<AttendanceReport>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_as!(
+        AttendanceReport,
+        "SELECT id, started_at, ended_at FROM attendance_report WHERE meeting_id = $1",
+        meeting_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(result)
+}
+
+/// The Entra ID object ids of the users who attended the session of the report.
+pub async fn get_attendee_ids<'a, E>(report_id: &str, executor: E) -> Result<Vec<String>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_scalar!(
+        "SELECT user_id FROM attendance WHERE report_id = $1",
+        report_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(result)
+}
@@ -0,0 +1,95 @@
+use sqlx::{Executor, Postgres};
+
+use crate::{
+    error::Result,
+    models::{ChannelAccount, ConversationAccount, ConversationReference},
+};
+
+struct ConversationReferenceRow {
+    activity_id: String,
+    conversation_id: String,
+    conversation_type: Option<String>,
+    tenant_id: Option<String>,
+    service_url: Option<String>,
+    channel_id: Option<String>,
+    bot_id: Option<String>,
+    bot_name: Option<String>,
+}
+
+impl From<ConversationReferenceRow> for ConversationReference {
+    fn from(row: ConversationReferenceRow) -> Self {
+        Self {
+            activity_id: Some(row.activity_id),
+            bot: row.bot_id.map(|id| ChannelAccount {
+                id,
+                name: row.bot_name,
+                ..Default::default()
+            }),
+            channel_id: row.channel_id,
+            conversation: ConversationAccount {
+                id: row.conversation_id,
+                conversation_type: row.conversation_type.unwrap_or_default(),
+                tenant_id: row.tenant_id.unwrap_or_default(),
+                ..Default::default()
+            },
+            service_url: row.service_url,
+        }
+    }
+}
+
+/// Saves where a message of the bot was posted. The reference must have the id of the message.
+pub async fn save_reference<'a, E>(reference: &ConversationReference, executor: E) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let bot = reference.bot.as_ref();
+
+    sqlx::query!(
+        "INSERT INTO conversation_reference (activity_id, conversation_id, conversation_type, tenant_id, service_url, channel_id, bot_id, bot_name) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (activity_id) DO UPDATE SET conversation_id = $2, conversation_type = $3, tenant_id = $4, service_url = $5, channel_id = $6, bot_id = $7, bot_name = $8",
+        reference.activity_id,
+        reference.conversation.id,
+        Some(&reference.conversation.conversation_type).filter(|x| !x.is_empty()),
+        Some(&reference.conversation.tenant_id).filter(|x| !x.is_empty()),
+        reference.service_url,
+        reference.channel_id,
+        bot.map(|x| &x.id),
+        bot.and_then(|x| x.name.as_ref()),
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_reference<'a, E>(
+    activity_id: &str,
+    executor: E,
+) -> Result<Option<ConversationReference>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_as!(
+        ConversationReferenceRow,
+        "SELECT activity_id, conversation_id, conversation_type, tenant_id, service_url, channel_id, bot_id, bot_name FROM conversation_reference WHERE activity_id = $1",
+        activity_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(result.map(ConversationReference::from))
+}
+
+pub async fn delete_reference<'a, E>(activity_id: &str, executor: E) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!(
+        "DELETE FROM conversation_reference WHERE activity_id = $1",
+        activity_id
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
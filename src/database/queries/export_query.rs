@@ -0,0 +1,64 @@
+use sqlx::{Executor, Postgres};
+
+use crate::error::Result;
+
+/// Keeps the content of an export until the user accepts or declines it.
+pub async fn create_export<'a, E>(
+    id: &str,
+    owner_id: &str,
+    content: &[u8],
+    executor: E,
+) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!(
+        "INSERT INTO export_file (id, owner_id, content) VALUES ($1, $2, $3)",
+        id,
+        owner_id,
+        content
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Returns the content of an export of the user, if it was not answered yet.
+pub async fn get_export<'a, E>(id: &str, owner_id: &str, executor: E) -> Result<Option<Vec<u8>>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_scalar!(
+        "SELECT content FROM export_file WHERE id = $1 AND owner_id = $2",
+        id,
+        owner_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(result)
+}
+
+pub async fn delete_export<'a, E>(id: &str, executor: E) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!("DELETE FROM export_file WHERE id = $1", id)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
+/// Deletes the exports whose file consent card was not answered within a day.
+pub async fn delete_expired_exports<'a, E>(executor: E) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!("DELETE FROM export_file WHERE created_at < NOW() - INTERVAL '1 day'")
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
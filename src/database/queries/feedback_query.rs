@@ -1,4 +1,6 @@
-use sqlx::{Executor, Postgres};
+use chrono::{DateTime, Utc};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use sqlx::{types::Json, Executor, Postgres, Transaction};
 
 use crate::error::Result;
 
@@ -7,22 +9,77 @@ pub struct Feedback {
     pub conversation_name: String,
     pub comment: Option<String>,
     pub rating: i64,
+    /// When the feedback was requested.
+    pub requested_at: DateTime<Utc>,
+    /// When the rating was last given or changed.
+    pub answered_at: DateTime<Utc>,
 }
 
-pub async fn create_feedback<'a, E>(
-    owner_id: &str,
+/// A feedback request, saved once its card is sent.
+pub struct NewFeedback<'a> {
+    pub owner_id: &'a str,
+    /// Id of the activity of the card.
+    pub card_id: &'a str,
+    pub instance_id: &'a str,
+    pub title: Option<&'a str>,
+    pub conversation_name: &'a str,
+    pub anonymous: bool,
+    /// Conversation the card was sent to.
+    pub conversation_id: &'a str,
+    /// Deadline after which the answers are rejected, if any.
+    pub closes_at: Option<DateTime<Utc>>,
+    /// Name of the template the questions come from, if any.
+    pub template: Option<&'a str>,
+    /// Online meeting the feedback was requested in, if any.
+    pub meeting_id: Option<&'a str>,
+    /// Entra ID object id of the organizer of the meeting.
+    pub organizer_id: Option<&'a str>,
+    pub organizer_name: Option<&'a str>,
+    /// Language of the owner, in which the cards of the feedback are written.
+    pub locale: &'a str,
+}
+
+pub async fn create_feedback<'a, E>(feedback: &NewFeedback<'_>, executor: E) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!(
+        "INSERT INTO feedback (id, owner_id, instance_id, title, conversation_name, anonymous, conversation_id, closes_at, template, meeting_id, organizer_id, organizer_name, locale) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
+        feedback.card_id,
+        feedback.owner_id,
+        feedback.instance_id,
+        feedback.title,
+        feedback.conversation_name,
+        feedback.anonymous,
+        feedback.conversation_id,
+        feedback.closes_at,
+        feedback.template,
+        feedback.meeting_id,
+        feedback.organizer_id,
+        feedback.organizer_name,
+        feedback.locale
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Ties the feedback to the session of its meeting it is about, its attendees being the ones targeted. Their count is cached for the response rate.
+pub async fn set_attendance_report<'a, E>(
     card_id: &str,
-    conversation_name: &str,
+    report_id: &str,
+    attendees_count: i32,
     executor: E,
 ) -> Result<()>
 where
     E: Executor<'a, Database = Postgres>,
 {
     sqlx::query!(
-        "INSERT INTO feedback (id, owner_id, conversation_name) VALUES ($1, $2, $3)",
-        card_id,
-        owner_id,
-        conversation_name
+        "UPDATE feedback SET attendance_report_id = $1, attendees_count = $2, updated_at = NOW() WHERE id = $3",
+        report_id,
+        attendees_count,
+        card_id
     )
     .execute(executor)
     .await?;
@@ -30,12 +87,42 @@ where
     Ok(())
 }
 
+/// Remembers that the session of the feedback was not found in the attendance reports, so Graph is not asked again on every vote.
+pub async fn set_attendance_checked<'a, E>(card_id: &str, executor: E) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!(
+        "UPDATE feedback SET attendance_checked_at = NOW() WHERE id = $1",
+        card_id
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Locks the feedback until the end of the transaction, so it cannot be closed while an answer is saved. Returns whether it is still open.
+pub async fn lock_open_feedback<'a, E>(card_id: &str, executor: E) -> Result<bool>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_scalar!(
+        "SELECT (closed_at IS NULL AND (closes_at IS NULL OR closes_at > NOW())) AS \"open!\" FROM feedback WHERE id = $1 FOR UPDATE",
+        card_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(result.unwrap_or_default())
+}
+
 pub async fn add_report<'a, E>(card_id: &str, report_id: &str, executor: E) -> Result<()>
 where
     E: Executor<'a, Database = Postgres>,
 {
     sqlx::query!(
-        "UPDATE feedback SET report_id = $1 WHERE id = $2",
+        "UPDATE feedback SET report_id = $1, updated_at = NOW() WHERE id = $2",
         report_id,
         card_id
     )
@@ -45,9 +132,11 @@ where
     Ok(())
 }
 
+/// Saves the answer of a respondent. `user_id` must be `None` for an anonymous feedback, the respondent being only known by its `respondent_key`.
 pub async fn create_or_update_feedback_entry<'a, E>(
     feedback_id: &str,
-    user_id: &str,
+    respondent_key: &str,
+    user_id: Option<&str>,
     rating: i32,
     comment: Option<&str>,
     executor: E,
@@ -56,8 +145,9 @@ where
     E: Executor<'a, Database = Postgres>,
 {
     sqlx::query!(
-        "INSERT INTO feedback_entry (feedback_id, user_id, rating, comment) VALUES ($1, $2, $3, $4) ON CONFLICT (feedback_id, user_id) DO UPDATE SET rating = $3, comment = $4",
+        "INSERT INTO feedback_entry (feedback_id, respondent_key, user_id, rating, comment) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (feedback_id, respondent_key) DO UPDATE SET rating = $4, comment = $5, updated_at = NOW()",
         feedback_id,
+        respondent_key,
         user_id,
         rating,
         comment,
@@ -77,7 +167,9 @@ where
         r#"SELECT 
             conversation_name, 
             rating, 
-            comment 
+            comment,
+            f.created_at AS requested_at,
+            fe.updated_at AS answered_at
         FROM 
             feedback f
             JOIN feedback_entry fe ON f.id = fe.feedback_id
@@ -92,29 +184,465 @@ where
 }
 
 pub struct FeedbackMetadata {
+    pub id: String,
+    pub anonymous: bool,
+    /// Whether the feedback was closed, or its deadline is over.
+    pub closed: bool,
     pub conversation_id: Option<String>,
     pub owner_id: String,
     pub report_id: Option<String>,
+    pub meeting_id: Option<String>,
+    pub organizer_id: Option<String>,
+    pub attendance_report_id: Option<String>,
+    /// The attendees of the session of the meeting, once known.
+    pub attendees_count: Option<i32>,
+    pub attendance_checked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub locale: String,
 }
 
-pub async fn get_feedback_by_id<'a, E>(card_id: &str, executor: E) -> Result<FeedbackMetadata>
+pub async fn get_feedback_by_instance_id<'a, E>(
+    instance_id: &str,
+    executor: E,
+) -> Result<Option<FeedbackMetadata>>
 where
     E: Executor<'a, Database = Postgres>,
 {
     let result = sqlx::query_as!(
         FeedbackMetadata,
         "SELECT 
+            feedback.id,
+            feedback.anonymous,
+            (feedback.closed_at IS NOT NULL OR feedback.closes_at <= NOW()) AS \"closed!\",
             \"user\".conversation_id,
             feedback.owner_id,
-            feedback.report_id 
+            feedback.report_id,
+            feedback.meeting_id,
+            feedback.organizer_id,
+            feedback.attendance_report_id,
+            feedback.attendees_count,
+            feedback.attendance_checked_at,
+            feedback.created_at,
+            feedback.locale
         FROM
             feedback 
             JOIN \"user\" ON feedback.owner_id = \"user\".id
         WHERE 
-            feedback.id = $1",
-        card_id
+            feedback.instance_id = $1",
+        instance_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(result)
+}
+
+pub struct FeedbackCard {
+    pub id: String,
+    pub title: Option<String>,
+    pub anonymous: bool,
+    pub owner_id: String,
+    /// Name of the organizer of the meeting the feedback is about, or else of its owner.
+    pub owner_name: Option<String>,
+    pub closes_at: Option<DateTime<Utc>>,
+    /// Whether the feedback was closed, or its deadline is over.
+    pub closed: bool,
+    pub template: Option<String>,
+    pub meeting_id: Option<String>,
+    pub organizer_id: Option<String>,
+    pub attendance_report_id: Option<String>,
+    pub attendance_checked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub locale: String,
+}
+
+pub async fn get_feedback_card_by_instance_id<'a, E>(
+    instance_id: &str,
+    executor: E,
+) -> Result<Option<FeedbackCard>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_as!(
+        FeedbackCard,
+        "SELECT 
+            feedback.id,
+            feedback.title,
+            feedback.anonymous,
+            feedback.owner_id,
+            COALESCE(feedback.organizer_name, \"user\".name) AS owner_name,
+            feedback.closes_at,
+            (feedback.closed_at IS NOT NULL OR feedback.closes_at <= NOW()) AS \"closed!\",
+            feedback.template,
+            feedback.meeting_id,
+            feedback.organizer_id,
+            feedback.attendance_report_id,
+            feedback.attendance_checked_at,
+            feedback.created_at,
+            feedback.locale
+        FROM
+            feedback 
+            JOIN \"user\" ON feedback.owner_id = \"user\".id
+        WHERE 
+            feedback.instance_id = $1",
+        instance_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(result)
+}
+
+pub struct FeedbackEntry {
+    pub rating: i32,
+    pub comment: Option<String>,
+}
+
+pub async fn get_feedback_entry<'a, E>(
+    feedback_id: &str,
+    respondent_key: &str,
+    executor: E,
+) -> Result<Option<FeedbackEntry>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_as!(
+        FeedbackEntry,
+        "SELECT rating, comment FROM feedback_entry WHERE feedback_id = $1 AND respondent_key = $2",
+        feedback_id,
+        respondent_key
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(result)
+}
+
+/// Closes the open feedbacks of the owner in the conversation, returning their instance ids.
+pub async fn close_feedbacks<'a, E>(
+    owner_id: &str,
+    conversation_id: &str,
+    executor: E,
+) -> Result<Vec<String>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_scalar!(
+        "UPDATE feedback SET closed_at = NOW(), updated_at = NOW() WHERE owner_id = $1 AND conversation_id = $2 AND closed_at IS NULL RETURNING instance_id AS \"instance_id!\"",
+        owner_id,
+        conversation_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(result)
+}
+
+/// Closes the feedback if its deadline is over and it is still open. Returns whether it was closed.
+pub async fn close_expired_feedback<'a, E>(instance_id: &str, executor: E) -> Result<bool>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query!(
+        "UPDATE feedback SET closed_at = NOW(), updated_at = NOW() WHERE instance_id = $1 AND closes_at <= NOW() AND closed_at IS NULL",
+        instance_id
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// A question of a feedback created from a template.
+#[derive(Clone, Debug)]
+pub struct FeedbackQuestion {
+    pub position: i32,
+    /// One of the [`crate::commands::templates::QuestionKind`], as text.
+    pub kind: String,
+    pub label: String,
+    pub choices: Vec<String>,
+}
+
+pub async fn add_question<'a, E>(
+    feedback_id: &str,
+    question: &FeedbackQuestion,
+    executor: E,
+) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!(
+        "INSERT INTO feedback_question (feedback_id, position, kind, label, choices) VALUES ($1, $2, $3, $4, $5)",
+        feedback_id,
+        question.position,
+        question.kind,
+        question.label,
+        &question.choices
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_questions<'a, E>(feedback_id: &str, executor: E) -> Result<Vec<FeedbackQuestion>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_as!(
+        FeedbackQuestion,
+        "SELECT position, kind, label, choices FROM feedback_question WHERE feedback_id = $1 ORDER BY position",
+        feedback_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(result)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedbackAnswer {
+    pub position: i32,
+    pub value: String,
+}
+
+/// Replaces the answers of a respondent, whose entry must already be saved.
+pub async fn set_answers(
+    feedback_id: &str,
+    respondent_key: &str,
+    answers: &[FeedbackAnswer],
+    tx: &mut Transaction<'_, Postgres>,
+) -> Result<()> {
+    sqlx::query!(
+        "DELETE FROM feedback_answer WHERE feedback_id = $1 AND respondent_key = $2",
+        feedback_id,
+        respondent_key
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    for answer in answers {
+        sqlx::query!(
+            "INSERT INTO feedback_answer (feedback_id, respondent_key, position, value) VALUES ($1, $2, $3, $4)",
+            feedback_id,
+            respondent_key,
+            answer.position,
+            answer.value
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Returns the answers of every respondent, or of a single one when `respondent_key` is given.
+pub async fn get_answers<'a, E>(
+    feedback_id: &str,
+    respondent_key: Option<&str>,
+    executor: E,
+) -> Result<Vec<FeedbackAnswer>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_as!(
+        FeedbackAnswer,
+        "SELECT position, value FROM feedback_answer WHERE feedback_id = $1 AND ($2::TEXT IS NULL OR respondent_key = $2) ORDER BY position",
+        feedback_id,
+        respondent_key
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(result)
+}
+
+/// An answer to a feedback, as written in the exports.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRow {
+    pub feedback_id: String,
+    pub title: Option<String>,
+    pub conversation_name: String,
+    pub requested_at: DateTime<Utc>,
+    pub closes_at: Option<DateTime<Utc>>,
+    pub closed_at: Option<DateTime<Utc>>,
+    /// The name of the respondent, only given when requested and when the feedback is not anonymous.
+    pub respondent_name: Option<String>,
+    pub rating: i32,
+    pub comment: Option<String>,
+    /// When the rating was last given or changed, only to the day when the feedback is anonymous,
+    /// so the answers cannot be matched with the order in which the report card was updated.
+    pub answered_at: DateTime<Utc>,
+    /// The answers to the questions of the template, in the order of the questions.
+    #[serde(serialize_with = "serialize_answers")]
+    pub answers: Vec<ExportAnswer>,
+}
+
+/// An answer to a question of a feedback template, as written in the exports.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ExportAnswer {
+    pub question: String,
+    pub value: String,
+}
+
+/// Writes the answers as an object keyed by the question, keeping the order of the questions.
+fn serialize_answers<S>(
+    answers: &[ExportAnswer],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(answers.len()))?;
+    for answer in answers {
+        map.serialize_entry(&answer.question, &answer.value)?;
+    }
+    map.end()
+}
+
+/// Returns the answers to the feedbacks sent in a conversation, restricted to those of a single owner when `owner_id` is given.
+pub async fn get_export_rows<'a, E>(
+    conversation_id: &str,
+    owner_id: Option<&str>,
+    with_names: bool,
+    executor: E,
+) -> Result<Vec<ExportRow>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query!(
+        r#"SELECT
+            f.id AS feedback_id,
+            f.title,
+            f.conversation_name,
+            f.created_at AS requested_at,
+            f.closes_at,
+            f.closed_at,
+            CASE WHEN $3 AND NOT f.anonymous THEN u.name END AS respondent_name,
+            fe.rating,
+            fe.comment,
+            CASE WHEN f.anonymous THEN date_trunc('day', fe.updated_at, 'UTC') ELSE fe.updated_at END AS "answered_at!",
+            COALESCE(
+                (
+                    SELECT jsonb_agg(jsonb_build_object('question', fq.label, 'value', fa.value) ORDER BY fq.position)
+                    FROM
+                        feedback_answer fa
+                        JOIN feedback_question fq ON fa.feedback_id = fq.feedback_id AND fa.position = fq.position
+                    WHERE fa.feedback_id = fe.feedback_id AND fa.respondent_key = fe.respondent_key
+                ),
+                '[]'
+            ) AS "answers!: Json<Vec<ExportAnswer>>"
+        FROM
+            feedback f
+            JOIN feedback_entry fe ON f.id = fe.feedback_id
+            LEFT JOIN "user" u ON fe.user_id = u.id
+        WHERE
+            f.conversation_id = $1
+            AND ($2::TEXT IS NULL OR f.owner_id = $2)
+        ORDER BY f.id, fe.respondent_key"#,
+        conversation_id,
+        owner_id,
+        with_names
     )
-    .fetch_one(executor)
+    .fetch_all(executor)
+    .await?
+    .into_iter()
+    .map(|x| ExportRow {
+        feedback_id: x.feedback_id,
+        title: x.title,
+        conversation_name: x.conversation_name,
+        requested_at: x.requested_at,
+        closes_at: x.closes_at,
+        closed_at: x.closed_at,
+        respondent_name: x.respondent_name,
+        rating: x.rating,
+        comment: x.comment,
+        answered_at: x.answered_at,
+        answers: x.answers.0,
+    })
+    .collect();
+
+    Ok(result)
+}
+
+/// A feedback request with the summary of its answers.
+#[derive(Clone, Debug)]
+pub struct FeedbackSession {
+    pub id: String,
+    pub title: Option<String>,
+    pub conversation_name: String,
+    pub created_at: DateTime<Utc>,
+    pub responses_count: i64,
+    /// The average rating, `None` when nobody answered.
+    pub average: Option<f64>,
+}
+
+/// Returns the last feedbacks sent to a conversation, the most recent first.
+pub async fn get_sessions<'a, E>(
+    conversation_id: &str,
+    limit: i64,
+    executor: E,
+) -> Result<Vec<FeedbackSession>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_as!(
+        FeedbackSession,
+        r#"SELECT
+            f.id,
+            f.title,
+            f.conversation_name,
+            f.created_at,
+            COUNT(fe.rating) AS "responses_count!",
+            AVG(fe.rating)::FLOAT8 AS average
+        FROM
+            feedback f
+            LEFT JOIN feedback_entry fe ON f.id = fe.feedback_id
+        WHERE
+            f.conversation_id = $1
+        GROUP BY f.id
+        ORDER BY f.created_at DESC
+        LIMIT $2"#,
+        conversation_id,
+        limit
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(result)
+}
+
+/// Returns the respondent keys of the entries of a feedback.
+pub async fn get_respondent_keys<'a, E>(feedback_id: &str, executor: E) -> Result<Vec<String>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_scalar!(
+        "SELECT respondent_key FROM feedback_entry WHERE feedback_id = $1",
+        feedback_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(result)
+}
+
+/// Returns the instance ids of the feedbacks of the owner in the conversation which still accept answers.
+pub async fn get_open_feedbacks<'a, E>(
+    owner_id: &str,
+    conversation_id: &str,
+    executor: E,
+) -> Result<Vec<String>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_scalar!(
+        "SELECT instance_id AS \"instance_id!\" FROM feedback WHERE owner_id = $1 AND conversation_id = $2 AND instance_id IS NOT NULL AND closed_at IS NULL AND (closes_at IS NULL OR closes_at > NOW())",
+        owner_id,
+        conversation_id
+    )
+    .fetch_all(executor)
     .await?;
 
     Ok(result)
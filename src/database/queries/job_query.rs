@@ -0,0 +1,136 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Executor, Postgres};
+
+use crate::error::Result;
+
+pub struct NewJob<'a> {
+    pub id: &'a str,
+    pub kind: &'a str,
+    pub payload: &'a serde_json::Value,
+    pub run_at: DateTime<Utc>,
+    pub max_attempts: i32,
+}
+
+pub async fn create_job<'a, E>(job: &NewJob<'_>, executor: E) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!(
+        "INSERT INTO job (id, kind, payload, run_at, max_attempts) VALUES ($1, $2, $3, $4, $5)",
+        job.id,
+        job.kind,
+        job.payload,
+        job.run_at,
+        job.max_attempts
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// A job leased by an instance.
+#[derive(Clone, Debug)]
+pub struct Job {
+    pub id: String,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    /// The number of attempts, including the current one.
+    pub attempts: i32,
+    pub max_attempts: i32,
+}
+
+/// Leases the jobs due to run which nobody else is running, for `lease_seconds`. The rows are skipped rather than waited for when another instance is leasing them.
+pub async fn lease_jobs<'a, E>(
+    worker_id: &str,
+    lease_seconds: f64,
+    limit: i64,
+    executor: E,
+) -> Result<Vec<Job>>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    let result = sqlx::query_as!(
+        Job,
+        "UPDATE job SET
+            locked_by = $1,
+            locked_until = NOW() + make_interval(secs => $2),
+            attempts = attempts + 1,
+            updated_at = NOW()
+        WHERE id IN (
+            SELECT id FROM job
+            WHERE completed_at IS NULL
+                AND failed_at IS NULL
+                AND run_at <= NOW()
+                AND (locked_until IS NULL OR locked_until < NOW())
+            ORDER BY run_at
+            LIMIT $3
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, kind, payload, attempts, max_attempts",
+        worker_id,
+        lease_seconds,
+        limit
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(result)
+}
+
+/// Marks a job as done, unless its lease was lost to another instance.
+pub async fn complete_job<'a, E>(id: &str, worker_id: &str, executor: E) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!(
+        "UPDATE job SET completed_at = NOW(), locked_by = NULL, locked_until = NULL, updated_at = NOW() WHERE id = $1 AND locked_by = $2",
+        id,
+        worker_id
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Releases a failed job so it runs again at `run_at`.
+pub async fn retry_job<'a, E>(
+    id: &str,
+    worker_id: &str,
+    run_at: DateTime<Utc>,
+    error: &str,
+    executor: E,
+) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!(
+        "UPDATE job SET run_at = $3, last_error = $4, locked_by = NULL, locked_until = NULL, updated_at = NOW() WHERE id = $1 AND locked_by = $2",
+        id,
+        worker_id,
+        run_at,
+        error
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Gives up a job whose attempts all failed.
+pub async fn fail_job<'a, E>(id: &str, worker_id: &str, error: &str, executor: E) -> Result<()>
+where
+    E: Executor<'a, Database = Postgres>,
+{
+    sqlx::query!(
+        "UPDATE job SET failed_at = NOW(), last_error = $3, locked_by = NULL, locked_until = NULL, updated_at = NOW() WHERE id = $1 AND locked_by = $2",
+        id,
+        worker_id,
+        error
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
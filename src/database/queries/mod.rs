@@ -1,2 +1,6 @@
+pub mod attendance_query;
+pub mod conversation_reference_query;
+pub mod export_query;
 pub mod feedback_query;
+pub mod job_query;
 pub mod user_query;
@@ -46,7 +46,7 @@ where
     E: Executor<'a, Database = Postgres>,
 {
     sqlx::query!(
-        "UPDATE \"user\" SET conversation_id = $1 WHERE id = $2",
+        "UPDATE \"user\" SET conversation_id = $1, updated_at = NOW() WHERE id = $2",
         conversation_id,
         user_id,
     )
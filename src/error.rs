@@ -2,6 +2,8 @@ use axum::{http::StatusCode, response::IntoResponse};
 use thiserror::Error;
 use tracing::warn;
 
+use crate::i18n::Locale;
+
 pub(crate) type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Error, Debug)]
@@ -15,19 +17,111 @@ pub enum Error {
     #[error("An error occured when trying to contact an external service.")]
     Service(serde_json::Value),
 
+    #[error("No command was given. Use the `help` command to know which ones are available.")]
+    MissingCommand,
+
     #[error("The command `{0}` is not a valid command. Use the `help` command to know which ones are available.")]
     UnknownCommand(String),
 
+    #[error(transparent)]
+    Arguments(#[from] crate::commands::args::ParseError),
+
+    #[error("The submitted card action is not supported.")]
+    UnknownAction(serde_json::Value),
+
+    #[error("No handler is registered for the jobs of kind `{0}`.")]
+    UnknownJob(String),
+
     #[error("The value `{0}` is missing.")]
     MissingValue(&'static str),
 
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    #[error("The credentials of the application could not be loaded : {0}")]
+    Credentials(String),
+
+    #[error("No token could be fetched from the identity provider, it failed moments ago.")]
+    TokenUnavailable,
+
+    #[error("The request could not be authenticated : {0}")]
+    Unauthorized(String),
+
+    #[error("The request is not valid : {0}")]
+    BadRequest(String),
+
+    #[error("{0} was not found.")]
+    NotFound(String),
+}
+
+impl Error {
+    /// Whether the error is caused by what the user sent, and can be explained to them. Any other error is an internal failure.
+    pub fn is_user_facing(&self) -> bool {
+        matches!(
+            self,
+            Error::MissingCommand
+                | Error::UnknownCommand(_)
+                | Error::Arguments(_)
+                | Error::UnknownAction(_)
+        )
+    }
+
+    /// The message explaining a user-facing error to the user.
+    pub fn user_message(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (Error::Arguments(e), locale) => e.user_message(locale),
+            (_, Locale::English) => self.to_string(),
+            (Error::MissingCommand, Locale::French) => "Aucune commande n'a été donnée. Utilisez la commande `help` pour connaître celles qui sont disponibles.".to_owned(),
+            (Error::UnknownCommand(command), Locale::French) => format!("La commande `{command}` n'existe pas. Utilisez la commande `help` pour connaître celles qui sont disponibles."),
+            (Error::UnknownAction(_), Locale::French) => "Cette carte n'est plus prise en charge. Elle a peut-être été envoyée par une ancienne version du bot, n'hésitez pas à en demander une nouvelle.".to_owned(),
+            (_, Locale::French) => "Une erreur est survenue.".to_owned(),
+        }
+    }
 }
 
 impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
         warn!("Error received : {:?}", self);
-        (StatusCode::INTERNAL_SERVER_ERROR, format!("{}", self)).into_response()
+        let status = match self {
+            Error::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Error::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Error::NotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, format!("{}", self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Error::MissingCommand, true)]
+    #[case(Error::UnknownCommand("foo".to_owned()), true)]
+    #[case(Error::UnknownAction(serde_json::Value::Null), true)]
+    #[case(Error::MissingValue("reply_to_id"), false)]
+    #[case(Error::Service(serde_json::Value::Null), false)]
+    fn test_is_user_facing(#[case] error: Error, #[case] expected: bool) {
+        // Act
+        let result = error.is_user_facing();
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[rstest]
+    #[case(Locale::English, "The command `foo` is not a valid command. Use the `help` command to know which ones are available.")]
+    #[case(Locale::French, "La commande `foo` n'existe pas. Utilisez la commande `help` pour connaître celles qui sont disponibles.")]
+    fn test_user_message(#[case] locale: Locale, #[case] expected: &str) {
+        // Act
+        let result = Error::UnknownCommand("foo".to_owned()).user_message(locale);
+
+        // Assert
+        assert_eq!(expected, result);
     }
 }
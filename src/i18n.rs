@@ -0,0 +1,85 @@
+use crate::models::Activity;
+
+/// The languages the bot can answer in. French is used when the locale of the user is unknown or not supported.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Locale {
+    #[default]
+    French,
+    English,
+}
+
+impl Locale {
+    /// Parses a locale such as `fr-FR` or `en-US`.
+    pub fn parse(locale: &str) -> Self {
+        match locale.split(['-', '_']).next() {
+            Some(language) if language.eq_ignore_ascii_case("en") => Locale::English,
+            _ => Locale::French,
+        }
+    }
+
+    /// The language, as stored with the feedbacks and read back by [`Locale::parse`].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::French => "fr",
+            Locale::English => "en",
+        }
+    }
+}
+
+/// A text written in every language of the bot.
+#[derive(Debug, PartialEq)]
+pub struct Text {
+    pub french: &'static str,
+    pub english: &'static str,
+}
+
+impl Text {
+    pub fn get(&self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::French => self.french,
+            Locale::English => self.english,
+        }
+    }
+}
+
+impl From<&Activity> for Locale {
+    fn from(activity: &Activity) -> Self {
+        activity
+            .locale
+            .as_deref()
+            .map(Locale::parse)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("fr-FR", Locale::French)]
+    #[case("fr", Locale::French)]
+    #[case("en-US", Locale::English)]
+    #[case("EN_gb", Locale::English)]
+    #[case("de-DE", Locale::French)]
+    #[case("", Locale::French)]
+    fn test_parse(#[case] locale: &str, #[case] expected: Locale) {
+        // Act
+        let result = Locale::parse(locale);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[rstest]
+    #[case(Locale::French)]
+    #[case(Locale::English)]
+    fn test_as_str_roundtrip(#[case] locale: Locale) {
+        // Act
+        let result = Locale::parse(locale.as_str());
+
+        // Assert
+        assert_eq!(locale, result);
+    }
+}
@@ -1,8 +1,11 @@
+pub mod auth;
 pub mod commands;
 pub mod database;
 pub mod error;
+pub mod i18n;
 pub mod models;
 pub mod routes;
+pub mod scheduler;
+pub mod services;
 pub mod state;
 pub mod utils;
-pub mod services;
@@ -1,9 +1,17 @@
-use std::env;
+use std::{env, fs, sync::Arc};
 
-use axum::{routing::post, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 use meet_a_bot::{
-    routes::message_route,
-    services::{GraphClient, TeamsClient},
+    auth::{ApiKeyAuthenticator, BotAuthenticator, OpenIdKeySource},
+    commands,
+    routes::{export_route, jobs_route, message_route, proactive_route},
+    services::{
+        credentials::{ClientCertificate, ClientSecret, ManagedIdentity, TokenFile},
+        CredentialProvider, GraphClient, RetryPolicy, TeamsClient,
+    },
     state::AppState,
 };
 use sqlx::PgPool;
@@ -27,13 +35,55 @@ async fn main() {
         .unwrap();
 
     let client_id = env::var("TEAMS_CLIENT_ID").expect("Missing TEAMS_CLIENT_ID");
-    let client_secret = env::var("TEAMS_CLIENT_SECRET").expect("Missing TEAMS_CLIENT_SECRET");
     let client_tenant = env::var("TEAMS_TENANT_ID").expect("Missing TEAMS_TENANT_ID");
     let db_url = env::var("DATABASE_URL").expect("Missing DATABASE_URL");
+    let anonymous_secret =
+        env::var("ANONYMOUS_FEEDBACK_SECRET").expect("Missing ANONYMOUS_FEEDBACK_SECRET");
+    let api_key = env::var("INTERNAL_API_KEY").expect("Missing INTERNAL_API_KEY");
+
+    let retry_policy = match env::var("HTTP_MAX_RETRIES") {
+        Ok(val) => RetryPolicy {
+            max_retries: val.parse().expect("HTTP_MAX_RETRIES is not a number!"),
+            ..Default::default()
+        },
+        Err(_) => RetryPolicy::default(),
+    };
 
     let client = reqwest::Client::new();
-    let teams_client = TeamsClient::new(client.clone(), &client_id, &client_secret);
-    let graph_client = GraphClient::new(client, &client_id, &client_secret, &client_tenant);
+
+    let credentials: Arc<dyn CredentialProvider> = match env::var("TEAMS_CREDENTIAL_TYPE")
+        .as_deref()
+    {
+        Ok("secret") | Err(_) => Arc::new(ClientSecret::new(
+            &env::var("TEAMS_CLIENT_SECRET").expect("Missing TEAMS_CLIENT_SECRET"),
+        )),
+        Ok("certificate") => {
+            let path = env::var("TEAMS_CERTIFICATE_PATH").expect("Missing TEAMS_CERTIFICATE_PATH");
+            let thumbprint = env::var("TEAMS_CERTIFICATE_THUMBPRINT")
+                .expect("Missing TEAMS_CERTIFICATE_THUMBPRINT");
+            let private_key = fs::read(path).expect("Unable to read the certificate");
+
+            Arc::new(
+                ClientCertificate::new(&private_key, &thumbprint).expect("Invalid certificate"),
+            )
+        }
+        Ok("token_file") => Arc::new(TokenFile::new(
+            env::var("AZURE_FEDERATED_TOKEN_FILE").expect("Missing AZURE_FEDERATED_TOKEN_FILE"),
+        )),
+        Ok("managed_identity") => Arc::new(ManagedIdentity::new(
+            client.clone(),
+            &env::var("IDENTITY_ENDPOINT").expect("Missing IDENTITY_ENDPOINT"),
+            &env::var("IDENTITY_HEADER").expect("Missing IDENTITY_HEADER"),
+            env::var("MANAGED_IDENTITY_CLIENT_ID").ok().as_deref(),
+        )),
+        Ok(other) => panic!("Unknown TEAMS_CREDENTIAL_TYPE `{other}`!"),
+    };
+
+    let teams_client = TeamsClient::new(client.clone(), &client_id, credentials.clone())
+        .with_retry_policy(retry_policy.clone());
+    let graph_client = GraphClient::new(client.clone(), &client_id, &client_tenant, credentials)
+        .with_retry_policy(retry_policy);
+    let authenticator = BotAuthenticator::new(Arc::new(OpenIdKeySource::new(client)), &client_id);
 
     let pool = PgPool::connect_lazy(&db_url).expect("Failed to connect to the database");
 
@@ -46,10 +96,23 @@ async fn main() {
         teams_client,
         graph_client,
         pool,
+        authenticator,
+        api_key_authenticator: ApiKeyAuthenticator::new(&api_key),
+        router: commands::router(),
+        scheduler: commands::scheduler(),
+        anonymous_secret: Arc::from(anonymous_secret),
     };
 
+    // The jobs are run by the `jobs` timer function, polling them is only meant for the local runs without the Functions host
+    if matches!(env::var("SCHEDULER_POLLING").as_deref(), Ok("true")) {
+        tokio::spawn(state.scheduler.clone().run(state.clone()));
+    }
+
     let app = Router::new()
         .route("/api/messages", post(message_route::handle))
+        .route("/api/export", get(export_route::handle))
+        .route("/api/proactive", post(proactive_route::handle))
+        .route("/jobs", post(jobs_route::handle))
         .with_state(state);
 
     axum::serve(listener, app.into_make_service())
@@ -1,9 +1,43 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+/// The data submitted from an Adaptive Card. Every card embeds the id of its instance, so the submission can be linked back to the card it comes from.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardAction {
+    pub instance_id: String,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+/// The actions which can be submitted from a card, discriminated by their `verb`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(untagged)]
+#[serde(tag = "verb")]
 pub enum Action {
+    #[serde(rename = "feedback")]
     Feedback(Feedback),
+    /// Sent by Teams to render the feedback card for the user viewing it.
+    #[serde(rename = "feedbackRefresh")]
+    FeedbackRefresh,
+    /// The answers to the questions of a feedback template.
+    #[serde(rename = "feedbackForm")]
+    FeedbackForm(FeedbackForm),
+}
+
+impl Action {
+    pub const FEEDBACK: &'static str = "feedback";
+    pub const FEEDBACK_REFRESH: &'static str = "feedbackRefresh";
+    pub const FEEDBACK_FORM: &'static str = "feedbackForm";
+
+    /// The verb used to route the action to its handler.
+    pub fn verb(&self) -> &'static str {
+        match self {
+            Action::Feedback(_) => Self::FEEDBACK,
+            Action::FeedbackRefresh => Self::FEEDBACK_REFRESH,
+            Action::FeedbackForm(_) => Self::FEEDBACK_FORM,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -11,3 +45,42 @@ pub struct Feedback {
     pub comment: Option<String>,
     pub rating: i32,
 }
+
+/// The values of the inputs of the form, keyed by their id.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FeedbackForm {
+    #[serde(flatten)]
+    pub answers: HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(serde_json::json!({ "verb": "feedback", "instanceId": "42", "rating": 4, "comment": "Top" }), Some(("42", Action::FEEDBACK)))]
+    #[case(serde_json::json!({ "verb": "feedback", "instanceId": "42", "rating": 4 }), Some(("42", Action::FEEDBACK)))]
+    #[case(serde_json::json!({ "verb": "feedbackRefresh", "instanceId": "42" }), Some(("42", Action::FEEDBACK_REFRESH)))]
+    #[case(serde_json::json!({ "verb": "feedbackForm", "instanceId": "42", "q0": "4", "q1": "yes" }), Some(("42", Action::FEEDBACK_FORM)))]
+    #[case(serde_json::json!({ "rating": 4 }), None)]
+    #[case(serde_json::json!({ "verb": "feedback", "rating": 4 }), None)]
+    #[case(serde_json::json!({ "verb": "poll", "instanceId": "42" }), None)]
+    #[case(serde_json::json!({ "verb": "feedback", "instanceId": "42", "rating": "four" }), None)]
+    fn test_card_action_deserialize(
+        #[case] value: serde_json::Value,
+        #[case] expected: Option<(&str, &str)>,
+    ) {
+        // Act
+        let result = serde_json::from_value::<CardAction>(value);
+
+        // Assert
+        assert_eq!(
+            expected,
+            result
+                .as_ref()
+                .ok()
+                .map(|x| (x.instance_id.as_str(), x.action.verb()))
+        );
+    }
+}
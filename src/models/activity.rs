@@ -6,14 +6,22 @@ use super::*;
 #[serde(rename_all = "camelCase")]
 pub struct Activity {
     pub r#type: Type,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
     pub from: ChannelAccount,
     pub recipient: ChannelAccount,
     pub conversation: ConversationAccount,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attachments: Option<Vec<Attachment>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub members_added: Option<Vec<ChannelAccount>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub reply_to_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub service_url: Option<String>,
@@ -69,4 +77,56 @@ impl Activity {
             },
         )
     }
+
+    /// The activity the responses should reply to, only in channels where the messages are threaded.
+    pub fn get_thread_id(&self) -> Option<&str> {
+        match self.conversation.conversation_type.as_str() {
+            "channel" => self.id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The reference of the conversation of the activity, the bot being its recipient.
+    pub fn get_conversation_reference(&self) -> ConversationReference {
+        ConversationReference {
+            activity_id: None,
+            bot: Some(self.recipient.clone()),
+            channel_id: self.channel_id.clone(),
+            conversation: self.conversation.clone(),
+            service_url: self.service_url.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("channel", Some("1700000000000"), Some("1700000000000"))]
+    #[case("channel", None, None)]
+    #[case("groupChat", Some("1700000000000"), None)]
+    #[case("personal", Some("1700000000000"), None)]
+    fn test_get_thread_id(
+        #[case] conversation_type: &str,
+        #[case] id: Option<&str>,
+        #[case] expected: Option<&str>,
+    ) {
+        // Arrange
+        let activity = Activity {
+            id: id.map(str::to_owned),
+            conversation: ConversationAccount {
+                conversation_type: conversation_type.to_owned(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Act
+        let result = activity.get_thread_id();
+
+        // Assert
+        assert_eq!(expected, result);
+    }
 }
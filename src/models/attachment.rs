@@ -10,6 +10,12 @@ pub struct Attachment {
     /// The media type of the content in the attachment.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content_type: Option<ContentType>,
+    /// URL for the content of the attachment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_url: Option<String>,
+    /// Name of the attachment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -38,6 +44,12 @@ pub enum ContentType {
     /// A rich card that plays videos. Set the content property to a VideoCard object.
     #[serde(rename = "application/vnd.microsoft.card.video")]
     Video,
+    /// A card asking the user for the permission to upload a file to their OneDrive. Set the content property to a FileConsentCard object.
+    #[serde(rename = "application/vnd.microsoft.teams.card.file.consent")]
+    FileConsent,
+    /// A card linking to a file uploaded to OneDrive. Set the content property to a FileInfoCard object.
+    #[serde(rename = "application/vnd.microsoft.teams.card.file.info")]
+    FileInfo,
     /// A media files. Set this property to known media types such as image/png, audio/wav, and video/mp4
     #[serde(untagged)]
     Media(String),
@@ -58,6 +70,14 @@ mod tests {
     #[case(ContentType::Signin, "\"application/vnd.microsoft.card.signin\"")]
     #[case(ContentType::Thumbnail, "\"application/vnd.microsoft.card.thumbnail\"")]
     #[case(ContentType::Video, "\"application/vnd.microsoft.card.video\"")]
+    #[case(
+        ContentType::FileConsent,
+        "\"application/vnd.microsoft.teams.card.file.consent\""
+    )]
+    #[case(
+        ContentType::FileInfo,
+        "\"application/vnd.microsoft.teams.card.file.info\""
+    )]
     #[case(ContentType::Media(String::from("image/png")), "\"image/png\"")]
     fn test_content_type_serialize(#[case] content_type: ContentType, #[case] expected: &str) {
         // Act
@@ -76,6 +96,10 @@ mod tests {
     #[case("\"application/vnd.microsoft.card.signin\"", ContentType::Signin)]
     #[case("\"application/vnd.microsoft.card.thumbnail\"", ContentType::Thumbnail)]
     #[case("\"application/vnd.microsoft.card.video\"", ContentType::Video)]
+    #[case(
+        "\"application/vnd.microsoft.teams.card.file.consent\"",
+        ContentType::FileConsent
+    )]
     #[case("\"image/png\"", ContentType::Media(String::from("image/png")))]
     fn test_content_type_deserialize(#[case] content_type: &str, #[case] expected: ContentType) {
         // Act
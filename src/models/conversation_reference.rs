@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ChannelAccount, ConversationAccount};
+
+/// Defines a particular point in a conversation, saved to send or update messages there outside of any incoming activity.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationReference {
+    /// ID of the activity to refer to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity_id: Option<String>,
+    /// The bot participating in this conversation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bot: Option<ChannelAccount>,
+    /// An ID that uniquely identifies the channel.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    /// The conversation, with its tenant.
+    pub conversation: ConversationAccount,
+    /// Service endpoint where operations concerning the referenced conversation may be performed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_url: Option<String>,
+}
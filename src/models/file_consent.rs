@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+/// The name of the invoke activities sent when the user accepts or declines a [`FileConsentCard`].
+pub const FILE_CONSENT_INVOKE: &str = "fileConsent/invoke";
+
+/// Asks the user for the permission to upload a file to their OneDrive, the bot being given an upload URL once accepted.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileConsentCard {
+    /// The description of the file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The size of the file, in bytes.
+    pub size_in_bytes: u64,
+    /// The context sent back to the bot when the user accepts the file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accept_context: Option<serde_json::Value>,
+    /// The context sent back to the bot when the user declines the file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decline_context: Option<serde_json::Value>,
+}
+
+/// Defines the value of a `fileConsent/invoke` activity.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileConsentCardResponse {
+    /// Either `accept` or `decline`.
+    pub action: String,
+    /// The accept or decline context of the card.
+    pub context: Option<serde_json::Value>,
+    /// Where to upload the file, only given when the file is accepted.
+    pub upload_info: Option<FileUploadInfo>,
+}
+
+/// Describes the file the user accepted to receive.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileUploadInfo {
+    /// The name of the file.
+    pub name: String,
+    /// The URL the content of the file must be uploaded to.
+    pub upload_url: String,
+    /// The URL of the file once uploaded.
+    pub content_url: String,
+    /// The id of the file in OneDrive.
+    pub unique_id: String,
+    /// The extension of the file.
+    pub file_type: String,
+}
+
+/// Links to a file uploaded to OneDrive.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileInfoCard {
+    /// The id of the file in OneDrive.
+    pub unique_id: String,
+    /// The extension of the file.
+    pub file_type: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_consent_card_serialize() {
+        // Arrange
+        let card = FileConsentCard {
+            description: Some("Export".to_owned()),
+            size_in_bytes: 42,
+            accept_context: Some(serde_json::json!({ "format": "csv" })),
+            decline_context: None,
+        };
+
+        // Act
+        let result = serde_json::to_value(card).unwrap();
+
+        // Assert
+        assert_eq!(
+            serde_json::json!({
+                "description": "Export",
+                "sizeInBytes": 42,
+                "acceptContext": { "format": "csv" }
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn test_file_consent_card_response_deserialize() {
+        // Arrange
+        let value = serde_json::json!({
+            "type": "fileUpload",
+            "action": "accept",
+            "context": { "format": "csv" },
+            "uploadInfo": {
+                "contentUrl": "https://contoso.sharepoint.com/feedbacks.csv",
+                "name": "feedbacks.csv",
+                "uploadUrl": "https://upload.example.com/session",
+                "uniqueId": "1150D938-8870-4044-9F2C-5BBDEBA70C8C",
+                "fileType": "csv"
+            }
+        });
+
+        // Act
+        let result: FileConsentCardResponse = serde_json::from_value(value).unwrap();
+
+        // Assert
+        assert_eq!("accept", result.action);
+        assert_eq!(Some(serde_json::json!({ "format": "csv" })), result.context);
+        let upload_info = result.upload_info.unwrap();
+        assert_eq!("https://upload.example.com/session", upload_info.upload_url);
+        assert_eq!("csv", upload_info.file_type);
+    }
+}
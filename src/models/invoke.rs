@@ -0,0 +1,150 @@
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use super::attachment::ContentType;
+
+/// The name of the invoke activities sent when an `Action.Execute` is triggered.
+pub const ADAPTIVE_CARD_ACTION: &str = "adaptiveCard/action";
+
+/// Defines the value of an `adaptiveCard/action` invoke activity.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptiveCardInvokeValue {
+    /// The action which was executed.
+    pub action: AdaptiveCardInvokeAction,
+    /// Either `manual` when the user triggered the action, or `automatic` for a refresh.
+    pub trigger: Option<String>,
+}
+
+/// Defines the `Action.Execute` which triggered the invoke.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptiveCardInvokeAction {
+    /// The verb of the action.
+    pub verb: String,
+    /// The data of the action, merged with the values of the inputs of the card.
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+impl AdaptiveCardInvokeValue {
+    /// Returns the data of the action with its verb, as a card submission would send it.
+    pub fn into_action_value(self) -> serde_json::Value {
+        let mut value = match self.action.data {
+            serde_json::Value::Object(data) => data,
+            _ => serde_json::Map::new(),
+        };
+        value.insert(
+            "verb".to_owned(),
+            serde_json::Value::String(self.action.verb),
+        );
+
+        serde_json::Value::Object(value)
+    }
+}
+
+/// Defines the response returned synchronously to an `adaptiveCard/action` invoke.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptiveCardInvokeResponse {
+    /// The HTTP status code of the response.
+    pub status_code: u16,
+    /// The type of the value, either an Adaptive Card or a message.
+    pub r#type: ContentType,
+    /// Either the card replacing the one the action was executed from, or a message shown to the user.
+    pub value: serde_json::Value,
+}
+
+impl AdaptiveCardInvokeResponse {
+    pub fn card(card: serde_json::Value) -> Self {
+        Self {
+            status_code: 200,
+            r#type: ContentType::Adaptive,
+            value: card,
+        }
+    }
+
+    pub fn message(message: &str) -> Self {
+        Self {
+            status_code: 200,
+            r#type: ContentType::Media("application/vnd.microsoft.activity.message".to_owned()),
+            value: serde_json::Value::String(message.to_owned()),
+        }
+    }
+
+    /// An error shown to the user by Teams, the card the action was executed from being left as is.
+    pub fn error(status: StatusCode, message: &str) -> Self {
+        Self {
+            status_code: status.as_u16(),
+            r#type: ContentType::Media("application/vnd.microsoft.error".to_owned()),
+            value: serde_json::json!({
+                "code": status.canonical_reason().unwrap_or_default(),
+                "message": message
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_action_value() {
+        // Arrange
+        let value: AdaptiveCardInvokeValue = serde_json::from_value(serde_json::json!({
+            "action": {
+                "type": "Action.Execute",
+                "verb": "feedback",
+                "data": { "instanceId": "42", "rating": 3, "comment": "Top" }
+            },
+            "trigger": "manual"
+        }))
+        .unwrap();
+
+        // Act
+        let result = value.into_action_value();
+
+        // Assert
+        assert_eq!(
+            serde_json::json!({ "verb": "feedback", "instanceId": "42", "rating": 3, "comment": "Top" }),
+            result
+        );
+    }
+
+    #[test]
+    fn test_message_serialize() {
+        // Act
+        let result = serde_json::to_value(AdaptiveCardInvokeResponse::message("Merci !")).unwrap();
+
+        // Assert
+        assert_eq!(
+            serde_json::json!({
+                "statusCode": 200,
+                "type": "application/vnd.microsoft.activity.message",
+                "value": "Merci !"
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn test_error_serialize() {
+        // Act
+        let result = serde_json::to_value(AdaptiveCardInvokeResponse::error(
+            StatusCode::BAD_REQUEST,
+            "Ce feedback est clos.",
+        ))
+        .unwrap();
+
+        // Assert
+        assert_eq!(
+            serde_json::json!({
+                "statusCode": 400,
+                "type": "application/vnd.microsoft.error",
+                "value": { "code": "Bad Request", "message": "Ce feedback est clos." }
+            }),
+            result
+        );
+    }
+}
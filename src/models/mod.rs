@@ -4,8 +4,12 @@ pub mod attachment;
 pub mod channel_account;
 pub mod conversation_account;
 pub mod conversation_parameters;
+pub mod conversation_reference;
 pub mod conversation_resource_response;
+pub mod file_consent;
+pub mod invoke;
 pub mod resource_response;
+pub mod teams_channel_account;
 
 pub use action::Action;
 pub use activity::Activity;
@@ -13,5 +17,8 @@ pub use attachment::Attachment;
 pub use channel_account::ChannelAccount;
 pub use conversation_account::ConversationAccount;
 pub use conversation_parameters::ConversationParameters;
+pub use conversation_reference::ConversationReference;
 pub use conversation_resource_response::ConversationResourceResponse;
+pub use invoke::AdaptiveCardInvokeResponse;
 pub use resource_response::ResourceResponse;
+pub use teams_channel_account::{TeamsChannelAccount, TeamsPagedMembersResult};
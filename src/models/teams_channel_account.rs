@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+/// Defines a user of Teams, as returned by the roster of a conversation.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamsChannelAccount {
+    /// Unique ID for the user on this channel.
+    pub id: String,
+    /// Display-friendly name of the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// This account's object ID within Microsoft Entra ID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aad_object_id: Option<String>,
+    /// Given name part of the user name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    /// Surname part of the user name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub surname: Option<String>,
+    /// Email of the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Unique user principal name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_principal_name: Option<String>,
+    /// Tenant ID of the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    /// Role of the user in the conversation, e.g. `user`, `guest` or `anonymous`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_role: Option<String>,
+}
+
+/// Defines a page of the members of a conversation.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamsPagedMembersResult {
+    /// The token to fetch the next page, missing on the last one.
+    pub continuation_token: Option<String>,
+    /// The members of the page.
+    #[serde(default)]
+    pub members: Vec<TeamsChannelAccount>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paged_members_result_deserialize() {
+        // Arrange
+        let value = serde_json::json!({
+            "continuationToken": "a:1",
+            "members": [
+                {
+                    "id": "29:1",
+                    "name": "John Doe",
+                    "aadObjectId": "6b2e4c3a",
+                    "givenName": "John",
+                    "surname": "Doe",
+                    "email": "john.doe@contoso.com",
+                    "userPrincipalName": "john.doe@contoso.com",
+                    "tenantId": "72f988bf",
+                    "userRole": "user"
+                }
+            ]
+        });
+
+        // Act
+        let result: TeamsPagedMembersResult = serde_json::from_value(value).unwrap();
+
+        // Assert
+        assert_eq!(Some("a:1"), result.continuation_token.as_deref());
+        let member = &result.members[0];
+        assert_eq!("29:1", member.id);
+        assert_eq!(Some("6b2e4c3a"), member.aad_object_id.as_deref());
+        assert_eq!(Some("John"), member.given_name.as_deref());
+        assert_eq!(
+            Some("john.doe@contoso.com"),
+            member.user_principal_name.as_deref()
+        );
+    }
+
+    #[test]
+    fn test_paged_members_result_deserialize_last_page() {
+        // Act
+        let result: TeamsPagedMembersResult =
+            serde_json::from_value(serde_json::json!({ "members": [] })).unwrap();
+
+        // Assert
+        assert!(result.continuation_token.is_none());
+        assert!(result.members.is_empty());
+    }
+}
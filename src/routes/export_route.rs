@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::{
+    auth::ApiKeyAuthenticated,
+    commands::feedback_export::{self, ExportFormat},
+    database::queries,
+    error::Result,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportQuery {
+    /// The conversation the feedbacks were sent to.
+    pub conversation_id: String,
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Whether the names of the respondents are exported, for the feedbacks which are not anonymous.
+    #[serde(default)]
+    pub names: bool,
+}
+
+/// Exports the answers to every feedback sent to a conversation, for the tools reading them outside of Teams.
+#[tracing::instrument(skip_all, fields(conversation_id = query.conversation_id))]
+pub async fn handle(
+    State(pool): State<PgPool>,
+    _: ApiKeyAuthenticated,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response> {
+    let rows =
+        queries::feedback_query::get_export_rows(&query.conversation_id, None, query.names, &pool)
+            .await?;
+    let content = feedback_export::build_export(&rows, query.format)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, query.format.media_type().to_owned()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"{}\"",
+                    feedback_export::get_file_name(query.format)
+                ),
+            ),
+        ],
+        content,
+    )
+        .into_response())
+}
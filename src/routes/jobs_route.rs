@@ -0,0 +1,38 @@
+use axum::{extract::State, Json};
+use serde::Serialize;
+
+use crate::{error::Result, state::AppState};
+
+/// The response of a custom handler to a trigger which is not HTTP, the timer having no output.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct InvokeResponse {
+    pub outputs: serde_json::Map<String, serde_json::Value>,
+    pub logs: Vec<String>,
+    pub return_value: Option<serde_json::Value>,
+}
+
+/// Runs the due jobs once, invoked by the Functions host on each tick of the `jobs` timer function. The host calls it on the port of the custom handler, which only forwards the routes of the HTTP functions from outside.
+#[tracing::instrument(skip_all)]
+pub async fn handle(State(state): State<AppState>) -> Result<Json<InvokeResponse>> {
+    state.scheduler.run_once(&state).await?;
+
+    Ok(Json(InvokeResponse::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invoke_response_serialize() {
+        // Act
+        let result = serde_json::to_value(InvokeResponse::default()).unwrap();
+
+        // Assert
+        assert_eq!(
+            serde_json::json!({ "Outputs": {}, "Logs": [], "ReturnValue": null }),
+            result
+        );
+    }
+}
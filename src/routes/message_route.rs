@@ -1,61 +1,143 @@
-use axum::{extract::State, response::IntoResponse, Json};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::{error, warn};
+use uuid::Uuid;
 
 use crate::{
+    auth::AuthenticatedActivity,
     commands::{
-        feedback_command::{handle_feedback_entry, send_feedback_card},
-        send_message, Commands,
+        feedback_export, get_error_adaptive_card,
+        router::{ActionResponse, Context},
+        send_adaptive_card, send_message,
+    },
+    i18n::Locale,
+    models::{
+        activity::{Activity, Type},
+        file_consent::FILE_CONSENT_INVOKE,
+        invoke::{AdaptiveCardInvokeValue, ADAPTIVE_CARD_ACTION},
+        AdaptiveCardInvokeResponse,
     },
-    models::activity::{Activity, Type},
-    services::teams_client::TeamsClient,
+    services::{teams_client::TeamsClient, RetryPolicy},
     state::AppState,
     utils::parse_command,
 };
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 
+/// Handles the activities sent by the Bot Framework. Errors are reported in the conversation rather than as an HTTP error, which Teams would not show.
 #[tracing::instrument(skip_all)]
 pub async fn handle(
-    State(AppState {
-        teams_client,
-        graph_client,
-        pool,
-    }): State<AppState>,
-    Json(activity): Json<Activity>,
-) -> Result<impl IntoResponse> {
+    State(state): State<AppState>,
+    AuthenticatedActivity(activity): AuthenticatedActivity,
+) -> Response {
+    // Teams drops an invoke after a few seconds, so its requests are not retried with the long backoff of the jobs
+    let state = match activity.r#type {
+        Type::Invoke => state.with_retry_policy(RetryPolicy::interactive()),
+        _ => state,
+    };
+    let context = Context::new(state, activity);
+
+    match handle_activity(&context).await {
+        Ok(response) => response,
+        Err(e) => report_error(&context, e).await,
+    }
+}
+
+async fn handle_activity(context: &Context) -> Result<Response> {
+    let Context { state, activity } = context;
+
     match activity.r#type {
-        Type::ConversationUpdate => send_greetings(&teams_client, &activity).await?,
+        Type::ConversationUpdate => send_greetings(&state.teams_client, activity).await?,
         Type::Message => {
             if activity.text.is_some() {
-                match parse_command(&activity) {
-                    Some(Commands::Feedback) => {
-                        send_feedback_card(&teams_client, &graph_client, &pool, &activity).await?
-                    }
-                    Some(Commands::Help) => {
-                        send_message(
-                            &teams_client,
-                            &activity,
-                            "Franchement ? De l'aide ? Gros y a que 2 commandes ...",
-                        )
-                        .await?
-                    }
-                    None => {
-                        send_message(&teams_client, &activity, "Failed to parse the command.")
-                            .await?
-                    }
-                }
+                let command = parse_command(activity).ok_or(Error::MissingCommand)??;
+                state.router.dispatch_command(context, command).await?;
             }
             if let Some(ref value) = activity.value {
-                if let Ok(crate::models::action::Action::Feedback(feedback)) =
-                    serde_json::from_value::<crate::models::action::Action>(value.clone())
+                if let ActionResponse::Message(message) =
+                    state.router.dispatch_action(context, value).await?
                 {
-                    handle_feedback_entry(&teams_client, &pool, &activity, &feedback).await?;
+                    send_message(&state.teams_client, activity, &message).await?;
                 }
             }
         }
+        Type::Invoke if activity.name.as_deref() == Some(ADAPTIVE_CARD_ACTION) => {
+            return handle_card_action(context).await;
+        }
+        Type::Invoke if activity.name.as_deref() == Some(FILE_CONSENT_INVOKE) => {
+            feedback_export::handle_file_consent(&state.teams_client, &state.pool, activity)
+                .await?;
+        }
         _ => (),
     }
 
-    Ok(())
+    Ok(StatusCode::OK.into_response())
+}
+
+/// Handles an `Action.Execute`, whose response is sent back synchronously to Teams.
+#[tracing::instrument(skip_all)]
+async fn handle_card_action(context: &Context) -> Result<Response> {
+    let value: AdaptiveCardInvokeValue = serde_json::from_value(
+        context
+            .activity
+            .value
+            .clone()
+            .ok_or(Error::MissingValue("value"))?,
+    )?;
+
+    let response = match context
+        .state
+        .router
+        .dispatch_action(context, &value.into_action_value())
+        .await?
+    {
+        ActionResponse::Card(card) => AdaptiveCardInvokeResponse::card(card),
+        ActionResponse::Message(message) => AdaptiveCardInvokeResponse::message(&message),
+        ActionResponse::Empty => return Ok(StatusCode::OK.into_response()),
+    };
+
+    Ok(Json(response).into_response())
+}
+
+/// Explains the error to the user. Internal errors are logged with a correlation id, which is also shown to the user so the logs can be found from their report.
+async fn report_error(context: &Context, e: Error) -> Response {
+    let Context { state, activity } = context;
+    let locale = Locale::from(activity);
+
+    let (status, message) = match e.is_user_facing() {
+        true => {
+            warn!("User error : {:?}", e);
+            (StatusCode::BAD_REQUEST, e.user_message(locale))
+        }
+        false => {
+            let correlation_id = Uuid::new_v4();
+            error!(%correlation_id, "Internal error : {:?}", e);
+            let message = match locale {
+                Locale::French => format!("Une erreur inattendue est survenue. Si le problème persiste, transmettez cette référence à l'équipe : {correlation_id}"),
+                Locale::English => format!("An unexpected error occured. If the problem persists, give this reference to the team : {correlation_id}"),
+            };
+            (StatusCode::INTERNAL_SERVER_ERROR, message)
+        }
+    };
+
+    match (&activity.r#type, activity.name.as_deref()) {
+        // The card the action was executed from is kept, so the user can try again
+        (Type::Invoke, Some(ADAPTIVE_CARD_ACTION)) => {
+            Json(AdaptiveCardInvokeResponse::error(status, &message)).into_response()
+        }
+        (Type::Message, _) | (Type::Invoke, Some(FILE_CONSENT_INVOKE)) => {
+            let card = get_error_adaptive_card(&message, locale);
+            if let Err(e) = send_adaptive_card(&state.teams_client, activity, &card).await {
+                error!("Unable to report the error to the user : {:?}", e);
+            }
+            StatusCode::OK.into_response()
+        }
+        _ => StatusCode::OK.into_response(),
+    }
 }
 
 #[tracing::instrument(skip_all)]
@@ -64,7 +146,7 @@ pub async fn send_greetings(client: &TeamsClient, activity: &Activity) -> Result
     let recipient = &activity.recipient;
 
     match (members_added, recipient) {
-        (Some(ref members_added), ref recipient) => {
+        (Some(ref members_added), recipient) => {
             let id = &recipient.id;
             if !members_added.iter().any(|x| x.id == *id) {
                 return Ok(());
@@ -73,7 +155,11 @@ pub async fn send_greetings(client: &TeamsClient, activity: &Activity) -> Result
         _ => return Ok(()),
     }
 
-    let message = format!("Salut ! Je suis {name}, prêt à rendre le meeting plus dynamique ! Pour en savoir plus, n'hésitez pas à me demander de l'aide ! (@{name} help)", name = activity.recipient.name.as_deref().unwrap_or("{bot_name}"));
+    let name = activity.recipient.name.as_deref().unwrap_or("{bot_name}");
+    let message = match Locale::from(activity) {
+        Locale::French => format!("Salut ! Je suis {name}, prêt à rendre le meeting plus dynamique ! Pour en savoir plus, n'hésitez pas à me demander de l'aide ! (@{name} help)"),
+        Locale::English => format!("Hi! I am {name}, ready to make the meeting more lively! To learn more, feel free to ask me for help! (@{name} help)"),
+    };
     send_message(client, activity, &message).await?;
 
     Ok(())
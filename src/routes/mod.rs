@@ -1 +1,4 @@
+pub mod export_route;
+pub mod jobs_route;
 pub mod message_route;
+pub mod proactive_route;
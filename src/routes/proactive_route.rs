@@ -0,0 +1,193 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::{
+    auth::ApiKeyAuthenticated,
+    database::queries,
+    error::{Error, Result},
+    models::{
+        activity::{Activity, Type},
+        attachment::ContentType,
+        Attachment, ConversationReference,
+    },
+    services::{proactive, TeamsClient},
+};
+
+/// What to do with the referenced conversation, identified by the id of a message the bot posted there.
+#[derive(Debug, Deserialize)]
+#[serde(
+    tag = "action",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ProactiveRequest {
+    /// Posts a new message in the conversation of the reference.
+    Send {
+        reference_id: String,
+        message: ProactiveMessage,
+    },
+    /// Replaces the referenced message.
+    Update {
+        reference_id: String,
+        message: ProactiveMessage,
+    },
+    /// Deletes the referenced message.
+    Delete { reference_id: String },
+}
+
+/// A message made of a text, an Adaptive Card, or both.
+#[derive(Debug, Default, Deserialize)]
+pub struct ProactiveMessage {
+    pub text: Option<String>,
+    pub card: Option<serde_json::Value>,
+}
+
+impl ProactiveMessage {
+    fn into_activity(self) -> Result<Activity> {
+        if self.text.is_none() && self.card.is_none() {
+            return Err(Error::BadRequest(
+                "The message needs a text or a card.".to_owned(),
+            ));
+        }
+
+        Ok(Activity {
+            r#type: Type::Message,
+            text: self.text,
+            attachments: self.card.map(|card| {
+                vec![Attachment {
+                    content: Some(card),
+                    content_type: Some(ContentType::Adaptive),
+                    ..Default::default()
+                }]
+            }),
+            ..Default::default()
+        })
+    }
+}
+
+impl ProactiveRequest {
+    fn reference_id(&self) -> &str {
+        match self {
+            ProactiveRequest::Send { reference_id, .. }
+            | ProactiveRequest::Update { reference_id, .. }
+            | ProactiveRequest::Delete { reference_id } => reference_id,
+        }
+    }
+}
+
+/// Sends, updates or deletes a message in a conversation the bot posted to before, for the services building reminders or scheduled posts. A sent message is saved as a new reference, whose id is returned.
+#[tracing::instrument(skip_all)]
+pub async fn handle(
+    State(client): State<TeamsClient>,
+    State(pool): State<PgPool>,
+    _: ApiKeyAuthenticated,
+    Json(request): Json<ProactiveRequest>,
+) -> Result<Response> {
+    let reference =
+        queries::conversation_reference_query::get_reference(request.reference_id(), &pool)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(format!("The reference `{}`", request.reference_id()))
+            })?;
+
+    match request {
+        ProactiveRequest::Send { message, .. } => {
+            let response =
+                proactive::send_to_reference(&client, &reference, &message.into_activity()?)
+                    .await?;
+
+            queries::conversation_reference_query::save_reference(
+                &ConversationReference {
+                    activity_id: Some(response.id.clone()),
+                    ..reference
+                },
+                &pool,
+            )
+            .await?;
+
+            Ok(Json(response).into_response())
+        }
+        ProactiveRequest::Update { message, .. } => {
+            proactive::update_reference(&client, &reference, &message.into_activity()?).await?;
+
+            Ok(StatusCode::NO_CONTENT.into_response())
+        }
+        ProactiveRequest::Delete { reference_id } => {
+            proactive::delete_reference(&client, &reference).await?;
+            queries::conversation_reference_query::delete_reference(&reference_id, &pool).await?;
+
+            Ok(StatusCode::NO_CONTENT.into_response())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_request_deserialize() {
+        // Arrange
+        let value = serde_json::json!({
+            "action": "send",
+            "referenceId": "1:abc",
+            "message": { "text": "N'oubliez pas le feedback !" }
+        });
+
+        // Act
+        let result: ProactiveRequest = serde_json::from_value(value).unwrap();
+
+        // Assert
+        assert_eq!("1:abc", result.reference_id());
+        let ProactiveRequest::Send { message, .. } = result else {
+            panic!("Expected a send request");
+        };
+        assert_eq!(Some("N'oubliez pas le feedback !"), message.text.as_deref());
+    }
+
+    #[test]
+    fn test_delete_request_deserialize() {
+        // Act
+        let result: ProactiveRequest = serde_json::from_value(
+            serde_json::json!({ "action": "delete", "referenceId": "1:abc" }),
+        )
+        .unwrap();
+
+        // Assert
+        assert!(
+            matches!(result, ProactiveRequest::Delete { ref reference_id } if reference_id == "1:abc")
+        );
+    }
+
+    #[rstest]
+    #[case(Some("Hello"), None, true)]
+    #[case(None, Some(serde_json::json!({ "type": "AdaptiveCard" })), true)]
+    #[case(None, None, false)]
+    fn test_into_activity(
+        #[case] text: Option<&str>,
+        #[case] card: Option<serde_json::Value>,
+        #[case] expected: bool,
+    ) {
+        // Arrange
+        let message = ProactiveMessage {
+            text: text.map(str::to_owned),
+            card: card.clone(),
+        };
+
+        // Act
+        let result = message.into_activity();
+
+        // Assert
+        assert_eq!(expected, result.is_ok());
+        if let Ok(activity) = result {
+            assert_eq!(card.is_some(), activity.attachments.is_some());
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::{Executor, Postgres};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{
+    database::queries::job_query::{self, NewJob},
+    error::{Error, Result},
+    state::AppState,
+};
+
+/// How often the due jobs are looked for when polling them.
+const POLL_PERIOD: Duration = Duration::from_secs(15);
+/// How long a job is reserved for the instance running it. A job still running past its lease may run twice.
+const LEASE: Duration = Duration::from_secs(5 * 60);
+/// The most jobs leased at once by an instance.
+const BATCH_SIZE: i64 = 10;
+/// How many times a job is attempted before giving up.
+const MAX_ATTEMPTS: i32 = 5;
+/// The delay before the first retry, doubled after each failure.
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(30);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// The payload of a job, identified by its kind.
+pub trait Job: Serialize + DeserializeOwned {
+    const KIND: &'static str;
+}
+
+/// Runs the jobs of a kind, registered with [`Scheduler::job`]. A job can run more than once, so the handler must be idempotent.
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    async fn run(&self, state: &AppState, payload: serde_json::Value) -> Result<()>;
+}
+
+/// Schedules a job to run at `run_at`, or as soon as possible if it is in the past. Enqueued in a transaction, the job only runs once it is committed.
+pub async fn enqueue<'a, J, E>(job: &J, run_at: DateTime<Utc>, executor: E) -> Result<String>
+where
+    J: Job,
+    E: Executor<'a, Database = Postgres>,
+{
+    let id = Uuid::new_v4().to_string();
+
+    job_query::create_job(
+        &NewJob {
+            id: &id,
+            kind: J::KIND,
+            payload: &serde_json::to_value(job)?,
+            run_at,
+            max_attempts: MAX_ATTEMPTS,
+        },
+        executor,
+    )
+    .await?;
+
+    Ok(id)
+}
+
+/// Runs the due jobs with the handler of their kind. Several instances of the bot may run the jobs at once, the leases making sure a job is run by one of them at a time.
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    handlers: HashMap<&'static str, Arc<dyn JobHandler>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn job(mut self, kind: &'static str, handler: impl JobHandler + 'static) -> Self {
+        self.handlers.insert(kind, Arc::new(handler));
+        self
+    }
+
+    /// Polls the due jobs forever, when no timer runs them. Never returns.
+    pub async fn run(self, state: AppState) {
+        let mut interval = tokio::time::interval(POLL_PERIOD);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.run_once(&state).await {
+                error!("An error occured while running the jobs : {:?}", e);
+            }
+        }
+    }
+
+    /// Runs the jobs due now, batch after batch, e.g. on each tick of the timer function.
+    pub async fn run_once(&self, state: &AppState) -> Result<()> {
+        let worker_id = Uuid::new_v4().to_string();
+
+        while self.run_due_jobs(state, &worker_id).await? == BATCH_SIZE as usize {}
+
+        Ok(())
+    }
+
+    /// Runs a batch of due jobs, returning how many were leased.
+    async fn run_due_jobs(&self, state: &AppState, worker_id: &str) -> Result<usize> {
+        let jobs =
+            job_query::lease_jobs(worker_id, LEASE.as_secs_f64(), BATCH_SIZE, &state.pool).await?;
+        let count = jobs.len();
+
+        for job in jobs {
+            let result = match self.handlers.get(job.kind.as_str()) {
+                Some(handler) => handler.run(state, job.payload).await,
+                None => Err(Error::UnknownJob(job.kind.clone())),
+            };
+
+            // A job whose outcome cannot be saved runs again once its lease expires, the next ones still run
+            let saved = match result {
+                Ok(()) => job_query::complete_job(&job.id, worker_id, &state.pool).await,
+                Err(e) if job.attempts < job.max_attempts => {
+                    warn!("The job {} ({}) failed : {:?}", job.id, job.kind, e);
+                    let run_at = Utc::now() + get_retry_delay(job.attempts);
+                    job_query::retry_job(&job.id, worker_id, run_at, &e.to_string(), &state.pool)
+                        .await
+                }
+                Err(e) => {
+                    error!(
+                        "The job {} ({}) failed for good : {:?}",
+                        job.id, job.kind, e
+                    );
+                    job_query::fail_job(&job.id, worker_id, &e.to_string(), &state.pool).await
+                }
+            };
+
+            if let Err(e) = saved {
+                error!(
+                    "An error occured while saving the outcome of the job {} ({}) : {:?}",
+                    job.id, job.kind, e
+                );
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+/// The delay before retrying a job after its given attempt failed, growing exponentially.
+fn get_retry_delay(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+
+    RETRY_BASE_DELAY
+        .saturating_mul(2u32.pow(exponent))
+        .min(MAX_RETRY_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, 30)]
+    #[case(2, 60)]
+    #[case(4, 240)]
+    #[case(8, 3600)]
+    #[case(100, 3600)]
+    #[case(0, 30)]
+    fn test_get_retry_delay(#[case] attempts: i32, #[case] expected: u64) {
+        // Act
+        let result = get_retry_delay(attempts);
+
+        // Assert
+        assert_eq!(Duration::from_secs(expected), result);
+    }
+}
@@ -0,0 +1,315 @@
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::header;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+
+const CLIENT_ASSERTION_TYPE: &str = "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+/// How long a signed client assertion is accepted. Entra ID only needs it for the token request.
+const ASSERTION_LIFETIME: u64 = 10 * 60;
+
+/// The audience of the managed identity tokens exchanged for a token of the application, see federated identity credentials.
+const TOKEN_EXCHANGE_AUDIENCE: &str = "api://AzureADTokenExchange";
+
+/// Proves the identity of the application when requesting a token with the client credentials flow.
+#[async_trait]
+pub trait CredentialProvider: Send + Sync {
+    /// Returns the form fields authenticating `client_id` to `token_url`, sent along the grant type and the scope.
+    async fn get_form_fields(
+        &self,
+        client_id: &str,
+        token_url: &str,
+    ) -> Result<Vec<(&'static str, String)>>;
+}
+
+/// A client secret of the app registration.
+pub struct ClientSecret {
+    secret: String,
+}
+
+impl ClientSecret {
+    pub fn new(secret: &str) -> Self {
+        Self {
+            secret: secret.to_owned(),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for ClientSecret {
+    async fn get_form_fields(&self, _: &str, _: &str) -> Result<Vec<(&'static str, String)>> {
+        Ok(vec![("client_secret", self.secret.clone())])
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AssertionClaims {
+    aud: String,
+    iss: String,
+    sub: String,
+    jti: String,
+    iat: u64,
+    nbf: u64,
+    exp: u64,
+}
+
+/// A certificate uploaded to the app registration, proving the identity with a client assertion signed by its private key.
+pub struct ClientCertificate {
+    key: EncodingKey,
+    /// The base64url encoded SHA-1 thumbprint of the certificate, identifying it to Entra ID.
+    thumbprint: String,
+}
+
+impl ClientCertificate {
+    /// `private_key` is the PEM encoded RSA key of the certificate, `thumbprint` its SHA-1 thumbprint in hexadecimal as shown by Entra ID.
+    pub fn new(private_key: &[u8], thumbprint: &str) -> Result<Self> {
+        let key = EncodingKey::from_rsa_pem(private_key)
+            .map_err(|e| Error::Credentials(format!("invalid private key ({e})")))?;
+        let thumbprint = hex::decode(thumbprint.replace(':', ""))
+            .map_err(|e| Error::Credentials(format!("invalid thumbprint ({e})")))?;
+
+        Ok(Self {
+            key,
+            thumbprint: URL_SAFE_NO_PAD.encode(thumbprint),
+        })
+    }
+
+    fn get_assertion(&self, client_id: &str, token_url: &str) -> Result<String> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut header = Header::new(Algorithm::RS256);
+        header.x5t = Some(self.thumbprint.clone());
+
+        let claims = AssertionClaims {
+            aud: token_url.to_owned(),
+            iss: client_id.to_owned(),
+            sub: client_id.to_owned(),
+            jti: Uuid::new_v4().to_string(),
+            iat: now,
+            nbf: now,
+            exp: now + ASSERTION_LIFETIME,
+        };
+
+        jsonwebtoken::encode(&header, &claims, &self.key)
+            .map_err(|e| Error::Credentials(format!("unable to sign the assertion ({e})")))
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for ClientCertificate {
+    async fn get_form_fields(
+        &self,
+        client_id: &str,
+        token_url: &str,
+    ) -> Result<Vec<(&'static str, String)>> {
+        Ok(vec![
+            ("client_assertion_type", CLIENT_ASSERTION_TYPE.to_owned()),
+            (
+                "client_assertion",
+                self.get_assertion(client_id, token_url)?,
+            ),
+        ])
+    }
+}
+
+/// A token issued by a trusted identity provider and written to a file, e.g. the service account token projected by the Azure workload identity. The file is read for every request, since it is rotated.
+pub struct TokenFile {
+    path: PathBuf,
+}
+
+impl TokenFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for TokenFile {
+    async fn get_form_fields(&self, _: &str, _: &str) -> Result<Vec<(&'static str, String)>> {
+        let token = tokio::fs::read_to_string(&self.path).await.map_err(|e| {
+            Error::Credentials(format!("unable to read {} ({e})", self.path.display()))
+        })?;
+
+        Ok(vec![
+            ("client_assertion_type", CLIENT_ASSERTION_TYPE.to_owned()),
+            ("client_assertion", token.trim().to_owned()),
+        ])
+    }
+}
+
+#[derive(Deserialize)]
+struct ManagedIdentityToken {
+    access_token: String,
+}
+
+/// The managed identity of the Azure Functions app, trusted by the app registration as a federated identity credential. Its token is exchanged for a token of the application.
+pub struct ManagedIdentity {
+    client: reqwest::Client,
+    endpoint: String,
+    secret: String,
+    /// The client id of a user-assigned identity, the system-assigned one being used when missing.
+    client_id: Option<String>,
+}
+
+impl ManagedIdentity {
+    /// `endpoint` and `secret` are the `IDENTITY_ENDPOINT` and `IDENTITY_HEADER` given by App Service to the app.
+    pub fn new(
+        client: reqwest::Client,
+        endpoint: &str,
+        secret: &str,
+        client_id: Option<&str>,
+    ) -> Self {
+        Self {
+            client,
+            endpoint: endpoint.to_owned(),
+            secret: secret.to_owned(),
+            client_id: client_id.map(str::to_owned),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for ManagedIdentity {
+    #[tracing::instrument(skip_all)]
+    async fn get_form_fields(&self, _: &str, _: &str) -> Result<Vec<(&'static str, String)>> {
+        let result = self
+            .client
+            .get(&self.endpoint)
+            .header("X-IDENTITY-HEADER", &self.secret)
+            .header(header::ACCEPT, "application/json")
+            .query(&[
+                ("api-version", Some("2019-08-01")),
+                ("resource", Some(TOKEN_EXCHANGE_AUDIENCE)),
+                ("client_id", self.client_id.as_deref()),
+            ])
+            .send()
+            .await?;
+
+        let token: ManagedIdentityToken = match result.status().is_success() {
+            false => return Err(Error::Service(result.json().await?)),
+            true => result.json().await?,
+        };
+
+        Ok(vec![
+            ("client_assertion_type", CLIENT_ASSERTION_TYPE.to_owned()),
+            ("client_assertion", token.access_token),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{DecodingKey, Validation};
+
+    use super::*;
+
+    const PRIVATE_KEY: &str = include_str!("../assets/tests/private_key.pem");
+    const PUBLIC_KEY: &str = include_str!("../assets/tests/public_key.pem");
+    const TOKEN_URL: &str = "https://login.microsoftonline.com/tenant/oauth2/v2.0/token";
+
+    #[tokio::test]
+    async fn test_client_secret() {
+        // Arrange
+        let credentials = ClientSecret::new("secret");
+
+        // Act
+        let result = credentials
+            .get_form_fields("client-id", TOKEN_URL)
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(vec![("client_secret", "secret".to_owned())], result);
+    }
+
+    #[tokio::test]
+    async fn test_client_certificate() {
+        // Arrange
+        let credentials = ClientCertificate::new(
+            PRIVATE_KEY.as_bytes(),
+            "AB:CD:EF:01:23:45:67:89:AB:CD:EF:01:23:45:67:89:AB:CD:EF:01",
+        )
+        .unwrap();
+
+        // Act
+        let result = credentials
+            .get_form_fields("client-id", TOKEN_URL)
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(
+            ("client_assertion_type", CLIENT_ASSERTION_TYPE.to_owned()),
+            result[0]
+        );
+        let assertion = &result[1].1;
+        let header = jsonwebtoken::decode_header(assertion).unwrap();
+        assert_eq!(Some("q83vASNFZ4mrze8BI0VniavN7wE"), header.x5t.as_deref());
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[TOKEN_URL]);
+        validation.set_issuer(&["client-id"]);
+        let claims = jsonwebtoken::decode::<AssertionClaims>(
+            assertion,
+            &DecodingKey::from_rsa_pem(PUBLIC_KEY.as_bytes()).unwrap(),
+            &validation,
+        )
+        .unwrap()
+        .claims;
+        assert_eq!("client-id", claims.sub);
+        assert_eq!(ASSERTION_LIFETIME, claims.exp - claims.iat);
+    }
+
+    #[test]
+    fn test_client_certificate_invalid_thumbprint() {
+        // Act
+        let result = ClientCertificate::new(PRIVATE_KEY.as_bytes(), "not hex");
+
+        // Assert
+        assert!(matches!(result, Err(Error::Credentials(_))));
+    }
+
+    #[tokio::test]
+    async fn test_token_file() {
+        // Arrange
+        let path = std::env::temp_dir().join(format!("token-{}", Uuid::new_v4()));
+        std::fs::write(&path, "federated-token\n").unwrap();
+        let credentials = TokenFile::new(&path);
+
+        // Act
+        let result = credentials.get_form_fields("client-id", TOKEN_URL).await;
+
+        // Assert
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            vec![
+                ("client_assertion_type", CLIENT_ASSERTION_TYPE.to_owned()),
+                ("client_assertion", "federated-token".to_owned()),
+            ],
+            result.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_file_missing() {
+        // Arrange
+        let credentials = TokenFile::new("/nonexistent/token");
+
+        // Act
+        let result = credentials.get_form_fields("client-id", TOKEN_URL).await;
+
+        // Assert
+        assert!(matches!(result, Err(Error::Credentials(_))));
+    }
+}
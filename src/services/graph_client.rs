@@ -1,8 +1,9 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
 use reqwest::Method;
-use serde::Deserialize;
-use std::ops::Deref;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{ops::Deref, sync::Arc};
 
-use super::BearerClient;
+use super::{BearerClient, CredentialProvider, RetryPolicy};
 use crate::error::{Error, Result};
 
 #[derive(Clone)]
@@ -22,24 +23,33 @@ impl GraphClient {
     pub fn new(
         client: reqwest::Client,
         client_id: &str,
-        client_secret: &str,
         tenant_id: &str,
+        credentials: Arc<dyn CredentialProvider>,
     ) -> Self {
-        let data = format!("grant_type=client_credentials&client_id={client_id}&client_secret={client_secret}&scope=https%3A%2F%2Fgraph.microsoft.com%2F.default");
         let token_url = format!("https://login.microsoftonline.com/{tenant_id}/oauth2/v2.0/token");
         let base_url = Some("https://graph.microsoft.com/v1.0".to_owned());
 
         Self {
-            client: BearerClient::new(client, token_url, data, base_url),
+            client: BearerClient::new(
+                client,
+                token_url,
+                client_id,
+                "https://graph.microsoft.com/.default",
+                credentials,
+                base_url,
+            ),
         }
     }
 
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry_policy);
+        self
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_chat(&self, chat_id: &str) -> Result<GetChatResponse> {
         let result = self
-            .create_request(Method::GET, &format!("chats/{chat_id}"))
-            .await?
-            .send()
+            .send(Method::GET, &format!("chats/{chat_id}"), |x| x)
             .await?;
 
         match result.status().is_success() {
@@ -47,9 +57,455 @@ impl GraphClient {
             true => Ok(result.json().await?),
         }
     }
+
+    /// Finds the online meeting of `organizer_id` joined with `join_web_url`, e.g. the one behind a meeting chat. The application must be granted an application access policy for the organizer.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_online_meeting(
+        &self,
+        organizer_id: &str,
+        join_web_url: &str,
+    ) -> Result<Option<OnlineMeeting>> {
+        let filter = format!("JoinWebUrl eq '{}'", join_web_url.replace('\'', "''"));
+        let result = self
+            .send(
+                Method::GET,
+                &format!("users/{organizer_id}/onlineMeetings"),
+                |x| x.query(&[("$filter", &filter)]),
+            )
+            .await?;
+
+        let response: CollectionResponse<OnlineMeeting> = match result.status().is_success() {
+            false => return Err(Error::Service(result.json().await?)),
+            true => result.json().await?,
+        };
+
+        Ok(response.value.into_iter().next())
+    }
+
+    /// Gets an event of the calendar of `user_id`, its dates being given in UTC.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_event(&self, user_id: &str, event_id: &str) -> Result<Event> {
+        let result = self
+            .send(
+                Method::GET,
+                &format!("users/{user_id}/events/{event_id}"),
+                |x| x.header("Prefer", "outlook.timezone=\"UTC\""),
+            )
+            .await?;
+
+        match result.status().is_success() {
+            false => Err(Error::Service(result.json().await?)),
+            true => Ok(result.json().await?),
+        }
+    }
+
+    /// Lists the attendance reports of an online meeting of `organizer_id`, one per session of the meeting. A report is only available once its session is over.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_attendance_reports(
+        &self,
+        organizer_id: &str,
+        meeting_id: &str,
+    ) -> Result<Vec<MeetingAttendanceReport>> {
+        self.get_all(&format!(
+            "users/{organizer_id}/onlineMeetings/{meeting_id}/attendanceReports"
+        ))
+        .await
+    }
+
+    /// Lists the attendees of a session of an online meeting of `organizer_id`.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_attendance_records(
+        &self,
+        organizer_id: &str,
+        meeting_id: &str,
+        report_id: &str,
+    ) -> Result<Vec<AttendanceRecord>> {
+        self.get_all(&format!(
+            "users/{organizer_id}/onlineMeetings/{meeting_id}/attendanceReports/{report_id}/attendanceRecords"
+        ))
+        .await
+    }
+
+    /// Reads a collection page by page, following the next links.
+    async fn get_all<T: DeserializeOwned>(&self, url: &str) -> Result<Vec<T>> {
+        let mut values = Vec::new();
+        let mut next_link = Some(url.to_owned());
+
+        while let Some(url) = next_link {
+            let result = self.send(Method::GET, &url, |x| x).await?;
+
+            let page: CollectionResponse<T> = match result.status().is_success() {
+                false => return Err(Error::Service(result.json().await?)),
+                true => result.json().await?,
+            };
+
+            values.extend(page.value);
+            next_link = page.next_link;
+        }
+
+        Ok(values)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionResponse<T> {
+    #[serde(default = "Vec::new")]
+    value: Vec<T>,
+    #[serde(rename = "@odata.nextLink")]
+    next_link: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GetChatResponse {
-    pub topic: String,
+    /// The subject of the chat, missing for one-on-one chats and some group chats.
+    pub topic: Option<String>,
+    /// `oneOnOne`, `group` or `meeting`.
+    pub chat_type: Option<String>,
+    /// The meeting the chat was created for, if any.
+    pub online_meeting_info: Option<TeamworkOnlineMeetingInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamworkOnlineMeetingInfo {
+    /// The id of the event in the calendar of the organizer.
+    pub calendar_event_id: Option<String>,
+    pub join_web_url: Option<String>,
+    pub organizer: Option<Identity>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Identity {
+    /// The Entra ID object id of the user.
+    pub id: Option<String>,
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnlineMeeting {
+    pub id: String,
+    pub subject: Option<String>,
+    pub start_date_time: Option<DateTime<Utc>>,
+    pub end_date_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub participants: MeetingParticipants,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct MeetingParticipants {
+    pub organizer: Option<MeetingParticipantInfo>,
+    #[serde(default)]
+    pub attendees: Vec<MeetingParticipantInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MeetingParticipantInfo {
+    pub upn: Option<String>,
+    pub identity: Option<IdentitySet>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IdentitySet {
+    pub user: Option<Identity>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Event {
+    pub id: String,
+    pub subject: Option<String>,
+    pub start: Option<DateTimeTimeZone>,
+    pub end: Option<DateTimeTimeZone>,
+    pub organizer: Option<Recipient>,
+    #[serde(default)]
+    pub attendees: Vec<Attendee>,
+}
+
+/// A date without offset, given in its time zone.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DateTimeTimeZone {
+    pub date_time: String,
+    pub time_zone: String,
+}
+
+impl DateTimeTimeZone {
+    /// The date, if it is given in UTC as asked by [`GraphClient::get_event`].
+    pub fn to_utc(&self) -> Option<DateTime<Utc>> {
+        match self.time_zone.as_str() {
+            "UTC" => NaiveDateTime::parse_from_str(&self.date_time, "%Y-%m-%dT%H:%M:%S%.f")
+                .ok()
+                .map(|x| x.and_utc()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recipient {
+    pub email_address: Option<EmailAddress>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EmailAddress {
+    pub name: Option<String>,
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attendee {
+    pub email_address: Option<EmailAddress>,
+    /// `required`, `optional` or `resource`.
+    #[serde(rename = "type")]
+    pub attendee_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingAttendanceReport {
+    pub id: String,
+    pub meeting_start_date_time: Option<DateTime<Utc>>,
+    pub meeting_end_date_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceRecord {
+    pub identity: Option<Identity>,
+    pub email_address: Option<String>,
+    /// `Organizer`, `Presenter` or `Attendee`.
+    pub role: Option<String>,
+    #[serde(default)]
+    pub total_attendance_in_seconds: i64,
+    #[serde(default)]
+    pub attendance_intervals: Vec<AttendanceInterval>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceInterval {
+    pub join_date_time: DateTime<Utc>,
+    pub leave_date_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub duration_in_seconds: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    #[test]
+    fn test_get_chat_response_deserialize_meeting() {
+        // Arrange
+        let value = serde_json::json!({
+            "id": "19:meeting_abc@thread.v2",
+            "topic": "Sprint review",
+            "chatType": "meeting",
+            "onlineMeetingInfo": {
+                "calendarEventId": "AAMkADAw",
+                "joinWebUrl": "https://teams.microsoft.com/l/meetup-join/19%3ameeting_abc%40thread.v2/0",
+                "organizer": {
+                    "id": "6b2e4c3a",
+                    "displayName": "Jane Doe",
+                    "userIdentityType": "aadUser"
+                }
+            }
+        });
+
+        // Act
+        let result: GetChatResponse = serde_json::from_value(value).unwrap();
+
+        // Assert
+        assert_eq!(Some("Sprint review"), result.topic.as_deref());
+        assert_eq!(Some("meeting"), result.chat_type.as_deref());
+        let info = result.online_meeting_info.unwrap();
+        assert_eq!(Some("AAMkADAw"), info.calendar_event_id.as_deref());
+        let organizer = info.organizer.unwrap();
+        assert_eq!(Some("6b2e4c3a"), organizer.id.as_deref());
+        assert_eq!(Some("Jane Doe"), organizer.display_name.as_deref());
+    }
+
+    #[test]
+    fn test_get_chat_response_deserialize_one_on_one() {
+        // Act
+        let result: GetChatResponse =
+            serde_json::from_value(serde_json::json!({ "topic": null, "chatType": "oneOnOne" }))
+                .unwrap();
+
+        // Assert
+        assert!(result.topic.is_none());
+        assert!(result.online_meeting_info.is_none());
+    }
+
+    #[test]
+    fn test_online_meetings_response_deserialize() {
+        // Arrange
+        let value = serde_json::json!({
+            "value": [
+                {
+                    "id": "MSo1N2Y5ZGFjYy03MWJm",
+                    "subject": "Sprint review",
+                    "startDateTime": "2026-10-18T09:00:00.0000000Z",
+                    "endDateTime": "2026-10-18T10:00:00.0000000Z",
+                    "participants": {
+                        "organizer": {
+                            "upn": "jane.doe@contoso.com",
+                            "identity": { "user": { "id": "6b2e4c3a", "displayName": "Jane Doe" } }
+                        },
+                        "attendees": [
+                            {
+                                "upn": "john.doe@contoso.com",
+                                "identity": { "user": { "id": "8c1d2e3f", "displayName": null } }
+                            }
+                        ]
+                    }
+                }
+            ]
+        });
+
+        // Act
+        let result: CollectionResponse<OnlineMeeting> = serde_json::from_value(value).unwrap();
+
+        // Assert
+        let meeting = &result.value[0];
+        assert_eq!("MSo1N2Y5ZGFjYy03MWJm", meeting.id);
+        assert_eq!(Some("Sprint review"), meeting.subject.as_deref());
+        assert_eq!(
+            Some(Utc.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap()),
+            meeting.start_date_time
+        );
+        let organizer = meeting.participants.organizer.as_ref().unwrap();
+        assert_eq!(Some("jane.doe@contoso.com"), organizer.upn.as_deref());
+        assert_eq!(1, meeting.participants.attendees.len());
+    }
+
+    #[test]
+    fn test_event_deserialize() {
+        // Arrange
+        let value = serde_json::json!({
+            "id": "AAMkADAw",
+            "subject": "Sprint review",
+            "start": { "dateTime": "2026-10-18T09:00:00.0000000", "timeZone": "UTC" },
+            "end": { "dateTime": "2026-10-18T10:00:00.0000000", "timeZone": "UTC" },
+            "organizer": { "emailAddress": { "name": "Jane Doe", "address": "jane.doe@contoso.com" } },
+            "attendees": [
+                {
+                    "type": "required",
+                    "status": { "response": "accepted", "time": "2026-10-17T08:00:00Z" },
+                    "emailAddress": { "name": "John Doe", "address": "john.doe@contoso.com" }
+                }
+            ]
+        });
+
+        // Act
+        let result: Event = serde_json::from_value(value).unwrap();
+
+        // Assert
+        assert_eq!(Some("Sprint review"), result.subject.as_deref());
+        let organizer = result.organizer.unwrap().email_address.unwrap();
+        assert_eq!(Some("Jane Doe"), organizer.name.as_deref());
+        assert_eq!(
+            Some("required"),
+            result.attendees[0].attendee_type.as_deref()
+        );
+    }
+
+    #[test]
+    fn test_attendance_records_deserialize() {
+        // Arrange
+        let value = serde_json::json!({
+            "@odata.nextLink": "https://graph.microsoft.com/v1.0/users/6b2e4c3a/onlineMeetings/MSo1/attendanceReports/c9b6/attendanceRecords?$skiptoken=abc",
+            "value": [
+                {
+                    "id": "8c1d2e3f",
+                    "emailAddress": "john.doe@contoso.com",
+                    "role": "Attendee",
+                    "identity": { "id": "8c1d2e3f", "displayName": "John Doe", "tenantId": null },
+                    "totalAttendanceInSeconds": 1800,
+                    "attendanceIntervals": [
+                        {
+                            "joinDateTime": "2026-10-18T09:02:00Z",
+                            "leaveDateTime": "2026-10-18T09:32:00Z",
+                            "durationInSeconds": 1800
+                        }
+                    ]
+                }
+            ]
+        });
+
+        // Act
+        let result: CollectionResponse<AttendanceRecord> = serde_json::from_value(value).unwrap();
+
+        // Assert
+        assert!(result.next_link.unwrap().contains("$skiptoken=abc"));
+        let record = &result.value[0];
+        assert_eq!(
+            Some("8c1d2e3f"),
+            record.identity.as_ref().and_then(|x| x.id.as_deref())
+        );
+        assert_eq!(1800, record.total_attendance_in_seconds);
+        assert_eq!(
+            Utc.with_ymd_and_hms(2026, 10, 18, 9, 2, 0).unwrap(),
+            record.attendance_intervals[0].join_date_time
+        );
+    }
+
+    #[test]
+    fn test_attendance_reports_deserialize_last_page() {
+        // Arrange
+        let value = serde_json::json!({
+            "value": [
+                {
+                    "id": "c9b6db1c",
+                    "totalParticipantCount": 2,
+                    "meetingStartDateTime": "2026-10-18T09:00:00.000Z",
+                    "meetingEndDateTime": "2026-10-18T10:00:00.000Z"
+                }
+            ]
+        });
+
+        // Act
+        let result: CollectionResponse<MeetingAttendanceReport> =
+            serde_json::from_value(value).unwrap();
+
+        // Assert
+        assert!(result.next_link.is_none());
+        assert_eq!("c9b6db1c", result.value[0].id);
+        assert_eq!(
+            Some(Utc.with_ymd_and_hms(2026, 10, 18, 10, 0, 0).unwrap()),
+            result.value[0].meeting_end_date_time
+        );
+    }
+
+    #[rstest]
+    #[case("2026-10-18T09:00:00.0000000", "UTC", Some((9, 0)))]
+    #[case("2026-10-18T09:30:00", "UTC", Some((9, 30)))]
+    #[case("2026-10-18T09:00:00.0000000", "Romance Standard Time", None)]
+    #[case("tomorrow", "UTC", None)]
+    fn test_date_time_time_zone_to_utc(
+        #[case] date_time: &str,
+        #[case] time_zone: &str,
+        #[case] expected: Option<(u32, u32)>,
+    ) {
+        // Arrange
+        let date = DateTimeTimeZone {
+            date_time: date_time.to_owned(),
+            time_zone: time_zone.to_owned(),
+        };
+
+        // Act
+        let result = date.to_utc();
+
+        // Assert
+        assert_eq!(
+            expected.map(|(h, m)| Utc.with_ymd_and_hms(2026, 10, 18, h, m, 0).unwrap()),
+            result
+        );
+    }
 }
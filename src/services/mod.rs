@@ -1,74 +1,80 @@
+pub mod credentials;
 pub mod graph_client;
+pub mod proactive;
+pub mod retry;
 pub mod teams_client;
+pub mod token_cache;
 
+pub use credentials::CredentialProvider;
 pub use graph_client::GraphClient;
+pub use retry::RetryPolicy;
 pub use teams_client::TeamsClient;
+pub use token_cache::{TokenMetrics, TokenMetricsSnapshot};
 
-use std::{
-    sync::Arc,
-    time::{Duration, Instant},
-};
+use std::{future::Future, sync::Arc, time::Instant};
 
-use reqwest::{header, Method, RequestBuilder};
-use serde::Deserialize;
-use tokio::sync::Mutex;
+use chrono::Utc;
+use reqwest::{Method, RequestBuilder, Response};
+use tracing::warn;
 
 use crate::error::{Error, Result};
 
-#[derive(Deserialize, Debug)]
-struct Token {
-    expires_in: usize,
-    access_token: String,
-    #[serde(skip, default = "Instant::now")]
-    acquired: Instant,
-}
-
-impl Token {
-    fn is_valid(&self) -> bool {
-        let elapsed = self.acquired.elapsed();
-        elapsed
-            < Duration::from_secs(self.expires_in as u64)
-                .checked_sub(Duration::from_secs(60))
-                .unwrap_or_default()
-    }
-}
+use self::token_cache::{CachedToken, Token, TokenCache};
 
 #[derive(Clone)]
 pub struct BearerClient {
     client: reqwest::Client,
     token_url: String,
-    form_data: String,
+    client_id: String,
+    scope: String,
+    credentials: Arc<dyn CredentialProvider>,
     base_url: Option<String>,
-    token: Arc<Mutex<Option<Token>>>,
+    tokens: Arc<TokenCache>,
+    retry_policy: RetryPolicy,
 }
 
 impl BearerClient {
     pub fn new(
         client: reqwest::Client,
         token_url: String,
-        form_data: String,
+        client_id: &str,
+        scope: &str,
+        credentials: Arc<dyn CredentialProvider>,
         base_url: Option<String>,
     ) -> Self {
         Self {
             client,
-            token_url: token_url.to_owned(),
-            form_data: form_data.to_owned(),
-            base_url: base_url.to_owned(),
-            token: Arc::new(Mutex::new(None)),
+            token_url,
+            client_id: client_id.to_owned(),
+            scope: scope.to_owned(),
+            credentials,
+            base_url,
+            tokens: Arc::default(),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Requests a token with the client credentials flow, the application being authenticated by the credential provider.
     #[tracing::instrument(skip(self))]
     async fn fetch_token(&self) -> Result<Token> {
+        let mut form = vec![
+            ("grant_type", "client_credentials".to_owned()),
+            ("client_id", self.client_id.clone()),
+            ("scope", self.scope.clone()),
+        ];
+        form.extend(
+            self.credentials
+                .get_form_fields(&self.client_id, &self.token_url)
+                .await?,
+        );
+
         let result = self
-            .client
-            .post(&self.token_url)
-            .header(
-                header::CONTENT_TYPE,
-                header::HeaderValue::from_static("application/x-www-form-urlencoded"),
-            )
-            .body(self.form_data.clone())
-            .send()
+            .send_anonymous(Method::POST, &self.token_url, |x| x.form(&form))
             .await?;
 
         match result.status().is_success() {
@@ -77,17 +83,80 @@ impl BearerClient {
         }
     }
 
-    #[tracing::instrument(skip(self))]
-    pub async fn create_request(&self, method: Method, url: &str) -> Result<RequestBuilder> {
-        let mut token = self.token.lock().await;
+    /// The token fetches of the client and its clones.
+    pub fn token_metrics(&self) -> TokenMetricsSnapshot {
+        self.tokens.metrics().snapshot()
+    }
+
+    /// Returns the cached token, refreshing it in the background when it is about to expire. A token is only waited for when none can be used.
+    async fn get_token(&self) -> Result<String> {
+        match self.tokens.get() {
+            CachedToken::Valid(token) => Ok(token),
+            CachedToken::Expiring(token) => {
+                self.refresh_in_background();
+                Ok(token)
+            }
+            // The token request is boxed, its retries otherwise nesting in the future of every request.
+            CachedToken::Missing => Box::pin(self.refresh()).await,
+        }
+    }
+
+    /// Fetches a token unless another caller is already doing it, in which case its token is used.
+    async fn refresh(&self) -> Result<String> {
+        let _guard = self.tokens.lock_refresh().await;
+
+        if let CachedToken::Valid(token) | CachedToken::Expiring(token) = self.tokens.get() {
+            return Ok(token);
+        }
+        if self.tokens.has_failed_recently() {
+            return Err(Error::TokenUnavailable);
+        }
+
+        self.fetch_and_cache().await
+    }
 
-        match *token {
-            Some(ref t) if !t.is_valid() => *token = Some(self.fetch_token().await?),
-            None => *token = Some(self.fetch_token().await?),
-            _ => (),
+    fn refresh_in_background(&self) {
+        let Some(guard) = self.tokens.try_lock_refresh() else {
+            return;
+        };
+
+        let client = self.clone();
+        tokio::spawn(async move {
+            let _guard = guard;
+            if let Err(e) = client.fetch_and_cache().await {
+                warn!("An error occured while refreshing the token : {:?}", e);
+            }
+        });
+    }
+
+    /// Fetches a token and caches it. The caller must hold the refresh lock.
+    async fn fetch_and_cache(&self) -> Result<String> {
+        let started = Instant::now();
+        let result = self.fetch_token().await;
+        self.tokens
+            .metrics()
+            .record(started.elapsed(), result.is_ok());
+
+        match result {
+            Ok(token) => {
+                let access_token = token.access_token.clone();
+                self.tokens.set(token);
+                Ok(access_token)
+            }
+            Err(e) => {
+                self.tokens.set_failure();
+                Err(e)
+            }
         }
+    }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn create_request(&self, method: Method, url: &str) -> Result<RequestBuilder> {
+        let token = self.get_token().await?;
+
+        // An absolute URL, e.g. the next link of a paged response, is already complete
         let url = match self.base_url {
+            Some(_) if url.starts_with("https://") => url.to_owned(),
             Some(ref base_url) => format!(
                 "{base_url}/{url}",
                 base_url = base_url.trim_end_matches('/'),
@@ -96,11 +165,77 @@ impl BearerClient {
             None => url.to_owned(),
         };
 
-        let request = self
-            .client
-            .request(method, url)
-            .bearer_auth(&token.as_ref().unwrap().access_token);
+        let request = self.client.request(method, url).bearer_auth(token);
 
         Ok(request)
     }
+
+    /// Sends a request with the bearer token, retrying it on the transient errors allowed by the retry policy. `build` adds the body or the query of the request, once per attempt.
+    pub async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        build: impl Fn(RequestBuilder) -> RequestBuilder,
+    ) -> Result<Response> {
+        self.execute(&method, || async {
+            Ok(build(self.create_request(method.clone(), url).await?))
+        })
+        .await
+    }
+
+    /// Sends a request without the bearer token, e.g. to a pre-authenticated URL, retrying it as [`Self::send`] does.
+    pub async fn send_anonymous(
+        &self,
+        method: Method,
+        url: &str,
+        build: impl Fn(RequestBuilder) -> RequestBuilder,
+    ) -> Result<Response> {
+        self.execute(&method, || async {
+            Ok(build(self.client.request(method.clone(), url)))
+        })
+        .await
+    }
+
+    /// Sends the request built by `request` until it succeeds, fails for good or runs out of retries. The response of the last attempt is returned whatever its status.
+    #[tracing::instrument(skip(self, request))]
+    async fn execute<F, Fut>(&self, method: &Method, request: F) -> Result<Response>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<RequestBuilder>>,
+    {
+        let policy = &self.retry_policy;
+        let mut attempt = 0;
+
+        loop {
+            let result = request().await?.send().await;
+
+            let delay = match result {
+                Ok(ref response) if retry::is_retryable_status(method, response.status()) => {
+                    match retry::get_retry_after(response.headers(), Utc::now()) {
+                        Some(delay) if delay > policy.max_delay => None,
+                        Some(delay) => Some(delay),
+                        None => Some(policy.get_backoff(attempt)),
+                    }
+                }
+                Err(ref e) if retry::is_retryable_error(method, e) => {
+                    Some(policy.get_backoff(attempt))
+                }
+                _ => None,
+            };
+
+            match delay {
+                Some(delay) if attempt < policy.max_retries => {
+                    attempt += 1;
+                    warn!(
+                        "The request failed, retrying in {:?} ({attempt}/{}) : {:?}",
+                        delay,
+                        policy.max_retries,
+                        result.as_ref().map(Response::status)
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                _ => return Ok(result?),
+            }
+        }
+    }
 }
@@ -0,0 +1,117 @@
+use crate::{
+    error::{Error, Result},
+    models::{Activity, ConversationReference, ResourceResponse},
+};
+
+use super::TeamsClient;
+
+/// Fills the sender and conversation of an activity from the reference, as a reply would from the incoming activity.
+fn apply_reference(reference: &ConversationReference, activity: &Activity) -> Activity {
+    Activity {
+        from: reference
+            .bot
+            .clone()
+            .unwrap_or_else(|| activity.from.clone()),
+        conversation: reference.conversation.clone(),
+        channel_id: reference.channel_id.clone(),
+        ..activity.clone()
+    }
+}
+
+/// Sends an activity to the conversation of a stored reference, outside of any incoming activity.
+#[tracing::instrument(skip_all, fields(conversation_id = reference.conversation.id))]
+pub async fn send_to_reference(
+    client: &TeamsClient,
+    reference: &ConversationReference,
+    activity: &Activity,
+) -> Result<ResourceResponse> {
+    client
+        .send_to_conversation(
+            reference.service_url.as_deref(),
+            &reference.conversation.id,
+            &apply_reference(reference, activity),
+        )
+        .await
+}
+
+/// Replaces the activity of a stored reference.
+#[tracing::instrument(skip_all, fields(conversation_id = reference.conversation.id))]
+pub async fn update_reference(
+    client: &TeamsClient,
+    reference: &ConversationReference,
+    activity: &Activity,
+) -> Result<ResourceResponse> {
+    let activity_id = reference
+        .activity_id
+        .as_deref()
+        .ok_or(Error::MissingValue("activity_id"))?;
+
+    client
+        .update_activity(
+            reference.service_url.as_deref(),
+            &reference.conversation.id,
+            activity_id,
+            &apply_reference(reference, activity),
+        )
+        .await
+}
+
+/// Deletes the activity of a stored reference.
+#[tracing::instrument(skip_all, fields(conversation_id = reference.conversation.id))]
+pub async fn delete_reference(
+    client: &TeamsClient,
+    reference: &ConversationReference,
+) -> Result<()> {
+    let activity_id = reference
+        .activity_id
+        .as_deref()
+        .ok_or(Error::MissingValue("activity_id"))?;
+
+    client
+        .delete_activity(
+            reference.service_url.as_deref(),
+            &reference.conversation.id,
+            activity_id,
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{activity::Type, ChannelAccount, ConversationAccount};
+
+    #[test]
+    fn test_apply_reference() {
+        // Arrange
+        let reference = ConversationReference {
+            activity_id: Some("1".to_owned()),
+            bot: Some(ChannelAccount {
+                id: "28:bot".to_owned(),
+                ..Default::default()
+            }),
+            channel_id: Some("msteams".to_owned()),
+            conversation: ConversationAccount {
+                id: "19:meeting".to_owned(),
+                tenant_id: "tenant".to_owned(),
+                ..Default::default()
+            },
+            service_url: Some("https://smba.trafficmanager.net/emea/".to_owned()),
+        };
+        let activity = Activity {
+            r#type: Type::Message,
+            text: Some("Hello".to_owned()),
+            ..Default::default()
+        };
+
+        // Act
+        let result = apply_reference(&reference, &activity);
+
+        // Assert
+        assert_eq!("28:bot", result.from.id);
+        assert_eq!("19:meeting", result.conversation.id);
+        assert_eq!("tenant", result.conversation.tenant_id);
+        assert_eq!(Some("msteams"), result.channel_id.as_deref());
+        assert_eq!(Some("Hello"), result.text.as_deref());
+    }
+}
@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::{header, Method, StatusCode};
+
+/// How a request failing with a transient error is retried.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// The most retries of a request, 0 disabling them.
+    pub max_retries: u32,
+    /// The delay before the first retry, doubled after each failure.
+    pub base_delay: Duration,
+    /// The longest delay between two attempts. A `Retry-After` asking for more gives up instead.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The policy of the requests sent while Teams waits for the response of an invoke, which it drops after about 5 seconds. A request is retried at most once and right away, a `Retry-After` giving up instead.
+    pub fn interactive() -> Self {
+        Self {
+            max_retries: 1,
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+
+    /// The delay before retrying the request after its given failed attempt, with a random jitter between half and all of it so the instances do not retry in sync.
+    pub fn get_backoff(&self, attempt: u32) -> Duration {
+        let delay = self.get_max_backoff(attempt);
+
+        rand::thread_rng().gen_range(delay / 2..=delay)
+    }
+
+    fn get_max_backoff(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+}
+
+/// Whether the request can be sent again without risking to apply it twice.
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+/// Whether a request which got the response status can be retried. Throttled (429) and unavailable (503) requests were not processed, so they are retried whatever the method, the other server errors only for idempotent methods.
+pub fn is_retryable_status(method: &Method, status: StatusCode) -> bool {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => true,
+        StatusCode::INTERNAL_SERVER_ERROR
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::GATEWAY_TIMEOUT => is_idempotent(method),
+        _ => false,
+    }
+}
+
+/// Whether a request which failed without a response can be retried. A request which could not connect was never sent, the others may have been processed.
+pub fn is_retryable_error(method: &Method, error: &reqwest::Error) -> bool {
+    error.is_connect() || (error.is_timeout() && is_idempotent(method))
+}
+
+/// Reads the delay asked by the `Retry-After` header, either in seconds or as an HTTP date.
+pub fn get_retry_after(headers: &header::HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let value = headers.get(header::RETRY_AFTER)?.to_str().ok()?.trim();
+
+    match value.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => {
+            let date = DateTime::parse_from_rfc2822(value).ok()?;
+            Some(
+                (date.with_timezone(&Utc) - now)
+                    .to_std()
+                    .unwrap_or_default(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 500)]
+    #[case(1, 1000)]
+    #[case(3, 4000)]
+    #[case(10, 30000)]
+    #[case(100, 30000)]
+    fn test_get_max_backoff(#[case] attempt: u32, #[case] expected: u64) {
+        // Arrange
+        let policy = RetryPolicy::default();
+
+        // Act
+        let result = policy.get_max_backoff(attempt);
+
+        // Assert
+        assert_eq!(Duration::from_millis(expected), result);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    fn test_get_interactive_backoff(#[case] attempt: u32) {
+        // Arrange
+        let policy = RetryPolicy::interactive();
+
+        // Act
+        let result = policy.get_backoff(attempt);
+
+        // Assert
+        assert_eq!(Duration::ZERO, result);
+    }
+
+    #[test]
+    fn test_get_backoff() {
+        // Arrange
+        let policy = RetryPolicy::default();
+
+        // Act
+        let result = policy.get_backoff(2);
+
+        // Assert
+        assert!((Duration::from_secs(1)..=Duration::from_secs(2)).contains(&result));
+    }
+
+    #[rstest]
+    #[case(Method::POST, StatusCode::TOO_MANY_REQUESTS, true)]
+    #[case(Method::POST, StatusCode::SERVICE_UNAVAILABLE, true)]
+    #[case(Method::POST, StatusCode::BAD_GATEWAY, false)]
+    #[case(Method::PUT, StatusCode::BAD_GATEWAY, true)]
+    #[case(Method::GET, StatusCode::INTERNAL_SERVER_ERROR, true)]
+    #[case(Method::GET, StatusCode::NOT_FOUND, false)]
+    #[case(Method::DELETE, StatusCode::BAD_REQUEST, false)]
+    fn test_is_retryable_status(
+        #[case] method: Method,
+        #[case] status: StatusCode,
+        #[case] expected: bool,
+    ) {
+        // Act
+        let result = is_retryable_status(&method, status);
+
+        // Assert
+        assert_eq!(expected, result);
+    }
+
+    #[rstest]
+    #[case(Some("120"), Some(120))]
+    #[case(Some("Sun, 18 Oct 2026 12:00:30 GMT"), Some(30))]
+    #[case(Some("Sun, 18 Oct 2026 11:59:00 GMT"), Some(0))]
+    #[case(Some("soon"), None)]
+    #[case(None, None)]
+    fn test_get_retry_after(#[case] value: Option<&str>, #[case] expected: Option<u64>) {
+        // Arrange
+        let mut headers = header::HeaderMap::new();
+        if let Some(value) = value {
+            headers.insert(header::RETRY_AFTER, value.parse().unwrap());
+        }
+        let now = Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+
+        // Act
+        let result = get_retry_after(&headers, now);
+
+        // Assert
+        assert_eq!(expected.map(Duration::from_secs), result);
+    }
+}
@@ -1,15 +1,17 @@
-use std::ops::Deref;
+use std::{ops::Deref, sync::Arc};
 
-use reqwest::Method;
+use reqwest::{header, Method};
 
 use crate::{
     error::{Error, Result},
     models::{
-        activity::Activity, ConversationParameters, ConversationResourceResponse, ResourceResponse,
+        activity::{Activity, Type},
+        ConversationParameters, ConversationResourceResponse, ResourceResponse,
+        TeamsChannelAccount, TeamsPagedMembersResult,
     },
 };
 
-use super::BearerClient;
+use super::{BearerClient, CredentialProvider, RetryPolicy};
 
 const BASE_URL: &str = "https://smba.trafficmanager.net/teams";
 
@@ -27,16 +29,31 @@ impl Deref for TeamsClient {
 }
 
 impl TeamsClient {
-    pub fn new(client: reqwest::Client, client_id: &str, client_secret: &str) -> Self {
-        let data = format!("grant_type=client_credentials&client_id={client_id}&client_secret={client_secret}&scope=https%3A%2F%2Fapi.botframework.com%2F.default");
+    pub fn new(
+        client: reqwest::Client,
+        client_id: &str,
+        credentials: Arc<dyn CredentialProvider>,
+    ) -> Self {
         let token_url =
             "https://login.microsoftonline.com/botframework.com/oauth2/v2.0/token".to_owned();
 
         Self {
-            client: BearerClient::new(client, token_url, data, None),
+            client: BearerClient::new(
+                client,
+                token_url,
+                client_id,
+                "https://api.botframework.com/.default",
+                credentials,
+                None,
+            ),
         }
     }
 
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry_policy);
+        self
+    }
+
     #[tracing::instrument(skip(self, body))]
     pub async fn create_conversation(
         &self,
@@ -44,16 +61,14 @@ impl TeamsClient {
         body: &ConversationParameters,
     ) -> Result<ConversationResourceResponse> {
         let result = self
-            .create_request(
+            .send(
                 Method::POST,
                 &format!(
                     "{base_url}/v3/conversations",
                     base_url = base_url.map_or(BASE_URL, |x| x.trim_end_matches('/'))
                 ),
+                |x| x.json(body),
             )
-            .await?
-            .json(body)
-            .send()
             .await?;
 
         match result.status().is_success() {
@@ -71,16 +86,134 @@ impl TeamsClient {
         body: &Activity,
     ) -> Result<ResourceResponse> {
         let result = self
-            .create_request(
+            .send(
                 Method::POST,
                 &format!(
                     "{base_url}/v3/conversations/{conversation_id}/activities",
                     base_url = base_url.map_or(BASE_URL, |x| x.trim_end_matches('/'))
                 ),
+                |x| x.json(body),
+            )
+            .await?;
+
+        match result.status().is_success() {
+            false => Err(Error::Service(result.json().await?)),
+            true => Ok(result.json().await?),
+        }
+    }
+
+    /// Sends an activity (message) to the specified conversation, as a reply to the specified activity. The activity will be added as a reply to another activity, if the channel supports it. If the channel does not support nested replies, Reply to Activity behaves like Send to Conversation.
+    #[tracing::instrument(skip(self, body))]
+    pub async fn reply_to_activity(
+        &self,
+        base_url: Option<&str>,
+        conversation_id: &str,
+        activity_id: &str,
+        body: &Activity,
+    ) -> Result<ResourceResponse> {
+        let result = self
+            .send(
+                Method::POST,
+                &format!(
+                    "{base_url}/v3/conversations/{conversation_id}/activities/{activity_id}",
+                    base_url = base_url.map_or(BASE_URL, |x| x.trim_end_matches('/'))
+                ),
+                |x| x.json(body),
+            )
+            .await?;
+
+        match result.status().is_success() {
+            false => Err(Error::Service(result.json().await?)),
+            true => Ok(result.json().await?),
+        }
+    }
+
+    /// Shows the typing indicator of the bot in the specified conversation, until its next message or for a few seconds.
+    #[tracing::instrument(skip(self))]
+    pub async fn send_typing(&self, base_url: Option<&str>, conversation_id: &str) -> Result<()> {
+        let body = Activity {
+            r#type: Type::Typing,
+            ..Default::default()
+        };
+
+        self.send_to_conversation(base_url, conversation_id, &body)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Enumerates the members of a conversation. This REST API takes a ConversationId and returns an array of TeamsChannelAccount objects representing the members of the conversation. Large conversations should be enumerated with [`Self::get_paged_members`] instead.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_conversation_members(
+        &self,
+        base_url: Option<&str>,
+        conversation_id: &str,
+    ) -> Result<Vec<TeamsChannelAccount>> {
+        let result = self
+            .send(
+                Method::GET,
+                &format!(
+                    "{base_url}/v3/conversations/{conversation_id}/members",
+                    base_url = base_url.map_or(BASE_URL, |x| x.trim_end_matches('/'))
+                ),
+                |x| x,
+            )
+            .await?;
+
+        match result.status().is_success() {
+            false => Err(Error::Service(result.json().await?)),
+            true => Ok(result.json().await?),
+        }
+    }
+
+    /// Enumerates the members of a conversation one page at a time. The first page is fetched without a continuation token, the next ones with the token of the previous page until it is missing.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_paged_members(
+        &self,
+        base_url: Option<&str>,
+        conversation_id: &str,
+        page_size: Option<u32>,
+        continuation_token: Option<&str>,
+    ) -> Result<TeamsPagedMembersResult> {
+        let result = self
+            .send(
+                Method::GET,
+                &format!(
+                    "{base_url}/v3/conversations/{conversation_id}/pagedmembers",
+                    base_url = base_url.map_or(BASE_URL, |x| x.trim_end_matches('/'))
+                ),
+                |x| {
+                    x.query(&[
+                        ("pageSize", page_size.map(|x| x.to_string())),
+                        ("continuationToken", continuation_token.map(str::to_owned)),
+                    ])
+                },
+            )
+            .await?;
+
+        match result.status().is_success() {
+            false => Err(Error::Service(result.json().await?)),
+            true => Ok(result.json().await?),
+        }
+    }
+
+    /// Gets a single member of a conversation, by its ID on the channel or its Microsoft Entra object ID.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_conversation_member(
+        &self,
+        base_url: Option<&str>,
+        conversation_id: &str,
+        member_id: &str,
+    ) -> Result<TeamsChannelAccount> {
+        let result = self
+            .send(
+                Method::GET,
+                &format!(
+                    "{base_url}/v3/conversations/{conversation_id}/members/{member_id}",
+                    base_url = base_url.map_or(BASE_URL, |x| x.trim_end_matches('/'))
+                ),
+                |x| x,
             )
-            .await?
-            .json(body)
-            .send()
             .await?;
 
         match result.status().is_success() {
@@ -99,16 +232,14 @@ impl TeamsClient {
         body: &Activity,
     ) -> Result<ResourceResponse> {
         let result = self
-            .create_request(
+            .send(
                 Method::PUT,
                 &format!(
                     "{base_url}/v3/conversations/{conversation_id}/activities/{activity_id}",
                     base_url = base_url.map_or(BASE_URL, |x| x.trim_end_matches('/'))
                 ),
+                |x| x.json(body),
             )
-            .await?
-            .json(body)
-            .send()
             .await?;
 
         match result.status().is_success() {
@@ -116,4 +247,50 @@ impl TeamsClient {
             true => Ok(result.json().await?),
         }
     }
+
+    /// Some channels allow you to delete an existing activity. If successful, this operation removes the specified activity from the specified conversation.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_activity(
+        &self,
+        base_url: Option<&str>,
+        conversation_id: &str,
+        activity_id: &str,
+    ) -> Result<()> {
+        let result = self
+            .send(
+                Method::DELETE,
+                &format!(
+                    "{base_url}/v3/conversations/{conversation_id}/activities/{activity_id}",
+                    base_url = base_url.map_or(BASE_URL, |x| x.trim_end_matches('/'))
+                ),
+                |x| x,
+            )
+            .await?;
+
+        match result.status().is_success() {
+            false => Err(Error::Service(result.json().await?)),
+            true => Ok(()),
+        }
+    }
+
+    /// Uploads the content of a file accepted through a file consent card to the upload URL given by Teams. The URL is pre-authenticated, so no bearer token is sent.
+    #[tracing::instrument(skip(self, content))]
+    pub async fn upload_file(&self, upload_url: &str, content: Vec<u8>) -> Result<()> {
+        let length = content.len();
+        let result = self
+            .send_anonymous(Method::PUT, upload_url, |x| {
+                x.header(header::CONTENT_LENGTH, length)
+                    .header(
+                        header::CONTENT_RANGE,
+                        format!("bytes 0-{last}/{length}", last = length.saturating_sub(1)),
+                    )
+                    .body(content.clone())
+            })
+            .await?;
+
+        match result.status().is_success() {
+            false => Err(Error::Service(result.json().await?)),
+            true => Ok(()),
+        }
+    }
 }
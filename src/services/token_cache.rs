@@ -0,0 +1,240 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tracing::info;
+
+/// The token is considered expired this long before its actual expiry, so it does not expire on its way.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+/// The token is refreshed in the background once it expires in less than this, the requests going on with the current one.
+const REFRESH_WINDOW: Duration = Duration::from_secs(5 * 60);
+/// How long the requests needing a token fail fast after a failed fetch, instead of queuing behind another doomed one.
+const FAILURE_COOLDOWN: Duration = Duration::from_secs(5);
+
+#[derive(Deserialize, Debug)]
+pub(super) struct Token {
+    expires_in: u64,
+    pub(super) access_token: String,
+    #[serde(skip, default = "Instant::now")]
+    acquired: Instant,
+}
+
+impl Token {
+    fn expires_in(&self) -> Duration {
+        Duration::from_secs(self.expires_in).saturating_sub(self.acquired.elapsed())
+    }
+
+    fn is_valid(&self) -> bool {
+        self.expires_in() > EXPIRY_MARGIN
+    }
+
+    fn needs_refresh(&self) -> bool {
+        self.expires_in() <= REFRESH_WINDOW
+    }
+}
+
+/// What the cache holds for a caller asking for a token.
+pub(super) enum CachedToken {
+    /// The token can be used as is.
+    Valid(String),
+    /// The token can still be used, but should be refreshed in the background.
+    Expiring(String),
+    /// No token can be used, one must be fetched.
+    Missing,
+}
+
+/// The token of a [`super::BearerClient`], shared by its clones. Reading it only takes a short synchronous lock, never held while a token is fetched.
+#[derive(Default)]
+pub(super) struct TokenCache {
+    token: RwLock<Option<Arc<Token>>>,
+    last_failure: RwLock<Option<Instant>>,
+    /// Held while fetching a token, so a single fetch runs at a time.
+    refresh_lock: Arc<Mutex<()>>,
+    metrics: TokenMetrics,
+}
+
+impl TokenCache {
+    pub(super) fn get(&self) -> CachedToken {
+        let token = self.token.read().unwrap_or_else(|e| e.into_inner()).clone();
+
+        match token {
+            Some(token) if token.is_valid() && token.needs_refresh() => {
+                CachedToken::Expiring(token.access_token.clone())
+            }
+            Some(token) if token.is_valid() => CachedToken::Valid(token.access_token.clone()),
+            _ => CachedToken::Missing,
+        }
+    }
+
+    pub(super) fn set(&self, token: Token) {
+        *self.token.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(token));
+        *self.last_failure.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    pub(super) fn set_failure(&self) {
+        *self.last_failure.write().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+    }
+
+    /// Whether the last fetch failed too recently to try again.
+    pub(super) fn has_failed_recently(&self) -> bool {
+        self.last_failure
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some_and(|x| x.elapsed() < FAILURE_COOLDOWN)
+    }
+
+    /// Waits for the ongoing fetch, if any, before fetching a token.
+    pub(super) async fn lock_refresh(&self) -> OwnedMutexGuard<()> {
+        self.refresh_lock.clone().lock_owned().await
+    }
+
+    /// Allows a fetch only if none is ongoing, for the background refreshes.
+    pub(super) fn try_lock_refresh(&self) -> Option<OwnedMutexGuard<()>> {
+        self.refresh_lock.clone().try_lock_owned().ok()
+    }
+
+    pub(super) fn metrics(&self) -> &TokenMetrics {
+        &self.metrics
+    }
+}
+
+/// Counts the token fetches of a client, to monitor the identity provider.
+#[derive(Debug, Default)]
+pub struct TokenMetrics {
+    fetches: AtomicU64,
+    failures: AtomicU64,
+    total_latency_ms: AtomicU64,
+    max_latency_ms: AtomicU64,
+}
+
+/// The token fetches of a client since it was created.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TokenMetricsSnapshot {
+    pub fetches: u64,
+    pub failures: u64,
+    pub average_latency: Option<Duration>,
+    pub max_latency: Duration,
+}
+
+impl TokenMetrics {
+    pub(super) fn record(&self, latency: Duration, success: bool) {
+        let latency_ms = latency.as_millis() as u64;
+
+        self.fetches.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+        self.total_latency_ms
+            .fetch_add(latency_ms, Ordering::Relaxed);
+        self.max_latency_ms.fetch_max(latency_ms, Ordering::Relaxed);
+
+        info!(latency_ms, success, "Token fetched");
+    }
+
+    pub fn snapshot(&self) -> TokenMetricsSnapshot {
+        let fetches = self.fetches.load(Ordering::Relaxed);
+        let total_latency_ms = self.total_latency_ms.load(Ordering::Relaxed);
+
+        TokenMetricsSnapshot {
+            fetches,
+            failures: self.failures.load(Ordering::Relaxed),
+            average_latency: total_latency_ms
+                .checked_div(fetches)
+                .map(Duration::from_millis),
+            max_latency: Duration::from_millis(self.max_latency_ms.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn token(expires_in: u64) -> Token {
+        Token {
+            expires_in,
+            access_token: "token".to_owned(),
+            acquired: Instant::now(),
+        }
+    }
+
+    #[rstest]
+    #[case(Some(3600), "valid")]
+    #[case(Some(120), "expiring")]
+    #[case(Some(30), "missing")]
+    #[case(None, "missing")]
+    fn test_get(#[case] expires_in: Option<u64>, #[case] expected: &str) {
+        // Arrange
+        let cache = TokenCache::default();
+        if let Some(expires_in) = expires_in {
+            cache.set(token(expires_in));
+        }
+
+        // Act
+        let result = cache.get();
+
+        // Assert
+        let result = match result {
+            CachedToken::Valid(_) => "valid",
+            CachedToken::Expiring(_) => "expiring",
+            CachedToken::Missing => "missing",
+        };
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_has_failed_recently() {
+        // Arrange
+        let cache = TokenCache::default();
+
+        // Act
+        cache.set_failure();
+
+        // Assert
+        assert!(cache.has_failed_recently());
+        cache.set(token(3600));
+        assert!(!cache.has_failed_recently());
+    }
+
+    #[tokio::test]
+    async fn test_try_lock_refresh() {
+        // Arrange
+        let cache = TokenCache::default();
+
+        // Act
+        let guard = cache.lock_refresh().await;
+
+        // Assert
+        assert!(cache.try_lock_refresh().is_none());
+        drop(guard);
+        assert!(cache.try_lock_refresh().is_some());
+    }
+
+    #[test]
+    fn test_metrics_snapshot() {
+        // Arrange
+        let metrics = TokenMetrics::default();
+
+        // Act
+        metrics.record(Duration::from_millis(100), true);
+        metrics.record(Duration::from_millis(300), false);
+
+        // Assert
+        assert_eq!(
+            TokenMetricsSnapshot {
+                fetches: 2,
+                failures: 1,
+                average_latency: Some(Duration::from_millis(200)),
+                max_latency: Duration::from_millis(300),
+            },
+            metrics.snapshot()
+        );
+    }
+}
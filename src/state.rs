@@ -1,10 +1,34 @@
+use std::sync::Arc;
+
+use axum::extract::FromRef;
 use sqlx::PgPool;
 
-use crate::services::{graph_client::GraphClient, teams_client::TeamsClient};
+use crate::{
+    auth::{ApiKeyAuthenticator, BotAuthenticator},
+    commands::router::CommandRouter,
+    scheduler::Scheduler,
+    services::{graph_client::GraphClient, teams_client::TeamsClient, RetryPolicy},
+};
 
-#[derive(Clone)]
+#[derive(Clone, FromRef)]
 pub struct AppState {
     pub teams_client: TeamsClient,
     pub graph_client: GraphClient,
     pub pool: PgPool,
+    pub authenticator: BotAuthenticator,
+    /// Authenticates the internal endpoints, such as the export.
+    pub api_key_authenticator: ApiKeyAuthenticator,
+    pub router: CommandRouter,
+    pub scheduler: Scheduler,
+    /// Secret used to hash the respondents of the anonymous feedbacks.
+    pub anonymous_secret: Arc<str>,
+}
+
+impl AppState {
+    /// The same state, whose clients retry their requests following `retry_policy`.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.teams_client = self.teams_client.with_retry_policy(retry_policy.clone());
+        self.graph_client = self.graph_client.with_retry_policy(retry_policy);
+        self
+    }
 }
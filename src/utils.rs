@@ -1,6 +1,10 @@
-use crate::{commands::Commands, models::activity::Activity};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 
-pub fn parse_command(activity: &Activity) -> Option<Commands> {
+use crate::{commands::Commands, error::Result, models::activity::Activity};
+
+/// Parses the command sent to the bot, ignoring the leading mention. Returns `None` when the message contains no command.
+pub fn parse_command(activity: &Activity) -> Option<Result<Commands>> {
     match activity.recipient.name {
         Some(ref name) => activity
             .text
@@ -9,16 +13,43 @@ pub fn parse_command(activity: &Activity) -> Option<Commands> {
                 text.strip_prefix(&format!("<at>{name}</at>"))
                     .or(Some(text))
             })
-            .and_then(|text| text.split_ascii_whitespace().next())
-            .and_then(|command| Commands::try_from(command).ok()),
+            .map(|text| text.trim())
+            .filter(|text| !text.is_empty())
+            .map(Commands::try_from),
         None => None,
     }
 }
 
+/// Identifies the respondent of a feedback. For an anonymous feedback, the user id is replaced by a keyed hash, which still prevents voting twice but cannot be traced back to the user without the secret.
+pub fn respondent_key(secret: &str, feedback_id: &str, user_id: &str, anonymous: bool) -> String {
+    if !anonymous {
+        return user_id.to_owned();
+    }
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(feedback_id.as_bytes());
+    mac.update(b":");
+    mac.update(user_id.as_bytes());
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use super::*;
-    use crate::models::channel_account::ChannelAccount;
+    use crate::{
+        commands::{
+            feedback_export::{ExportArgs, ExportFormat},
+            feedback_history::HistoryArgs,
+            registry::FEEDBACK,
+            templates::RETRO,
+            FeedbackArgs,
+        },
+        models::channel_account::ChannelAccount,
+    };
     use rstest::rstest;
 
     #[rstest]
@@ -26,13 +57,92 @@ mod tests {
     #[case(None, Some("help"), None)]
     #[case(Some("Foo"), None, None)]
     #[case(Some("Foo"), Some("<at>Foo</at>"), None)]
-    #[case(Some("Foo"), Some("feedback"), Some(Commands::Feedback))]
-    #[case(Some("Foo"), Some("<at>Foo</at>feedback"), Some(Commands::Feedback))]
-    #[case(Some("Foo"), Some("<at>Foo</at> feedback"), Some(Commands::Feedback))]
+    #[case(
+        Some("Foo"),
+        Some("feedback"),
+        Some(Commands::Feedback(FeedbackArgs::default()))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at>feedback"),
+        Some(Commands::Feedback(FeedbackArgs::default()))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback"),
+        Some(Commands::Feedback(FeedbackArgs::default()))
+    )]
     #[case(
         Some("Foo"),
         Some("<at>Foo</at> feedback baz"),
-        Some(Commands::Feedback)
+        Some(Commands::Feedback(FeedbackArgs { title: Some("baz".to_owned()), ..Default::default() }))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback \"Sprint review\""),
+        Some(Commands::Feedback(FeedbackArgs { title: Some("Sprint review".to_owned()), ..Default::default() }))
+    )]
+    #[case(Some("Foo"), Some("<at>Foo</at> help"), Some(Commands::Help(None)))]
+    #[case(Some("Foo"), Some("<at>Foo</at> aide avis"), Some(Commands::Help(Some(&FEEDBACK))))]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback --anonymous \"Sprint review\""),
+        Some(Commands::Feedback(FeedbackArgs { anonymous: true, title: Some("Sprint review".to_owned()), ..Default::default() }))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback Rétro --close-in 1h30m"),
+        Some(Commands::Feedback(FeedbackArgs { close_in: Some(Duration::from_secs(90 * 60)), title: Some("Rétro".to_owned()), ..Default::default() }))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback close"),
+        Some(Commands::FeedbackClose)
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback \"Close\""),
+        Some(Commands::Feedback(FeedbackArgs { title: Some("Close".to_owned()), ..Default::default() }))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback 'History' --anonymous"),
+        Some(Commands::Feedback(FeedbackArgs { anonymous: true, title: Some("History".to_owned()), ..Default::default() }))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback \"--anonymous\""),
+        Some(Commands::Feedback(FeedbackArgs { title: Some("--anonymous".to_owned()), ..Default::default() }))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback remind"),
+        Some(Commands::FeedbackRemind)
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback --remind-in 2h"),
+        Some(Commands::Feedback(FeedbackArgs { remind_in: Some(Duration::from_secs(2 * 60 * 60)), ..Default::default() }))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback history --last 10"),
+        Some(Commands::FeedbackHistory(HistoryArgs { last: Some(10) }))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback export"),
+        Some(Commands::FeedbackExport(ExportArgs::default()))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback export --format json --names"),
+        Some(Commands::FeedbackExport(ExportArgs { format: ExportFormat::Json, names: true }))
+    )]
+    #[case(
+        Some("Foo"),
+        Some("<at>Foo</at> feedback --template retro"),
+        Some(Commands::Feedback(FeedbackArgs { template: Some(&RETRO), ..Default::default() }))
     )]
     fn test_parse_command(
         #[case] name: Option<&str>,
@@ -40,19 +150,94 @@ mod tests {
         #[case] expected: Option<Commands>,
     ) {
         // Arrange
-        let activity = Activity {
+        let activity = activity(name, text);
+
+        // Act
+        let result = parse_command(&activity);
+
+        // Assert
+        assert_eq!(expected, result.map(|x| x.unwrap()));
+    }
+
+    #[rstest]
+    #[case("<at>Foo</at> unknown", "The command `unknown` is not a valid command. Use the `help` command to know which ones are available.")]
+    #[case("<at>Foo</at> feedback a b", "The argument `b` was not expected.")]
+    #[case(
+        "<at>Foo</at> feedback --template poll",
+        "The value `poll` is not valid for `--template` : expected a template among `retro` and `meeting`."
+    )]
+    #[case(
+        "<at>Foo</at> feedback export --format xlsx",
+        "The value `xlsx` is not valid for `--format` : expected a format among `csv` and `json`."
+    )]
+    #[case("<at>Foo</at> help unknown", "The command `unknown` is not a valid command. Use the `help` command to know which ones are available.")]
+    #[case(
+        "<at>Foo</at> feedback --foo",
+        "The option `--foo` does not exist for this command."
+    )]
+    #[case(
+        "<at>Foo</at> feedback \"Sprint",
+        "The quote opened at position 9 is never closed."
+    )]
+    fn test_parse_command_error(#[case] text: &str, #[case] expected: &str) {
+        // Arrange
+        let activity = activity(Some("Foo"), Some(text));
+
+        // Act
+        let result = parse_command(&activity);
+
+        // Assert
+        assert_eq!(
+            Some(expected.to_owned()),
+            result.map(|x| x.unwrap_err().to_string())
+        );
+    }
+
+    fn activity(name: Option<&str>, text: Option<&str>) -> Activity {
+        Activity {
             recipient: name.map_or(ChannelAccount::default(), |name| ChannelAccount {
                 name: Some(name.to_owned()),
                 ..Default::default()
             }),
             text: text.map(|x| x.to_owned()),
             ..Default::default()
-        };
+        }
+    }
 
+    #[rstest]
+    #[case("secret", "feedback", "user", false, "user")]
+    #[case(
+        "secret",
+        "feedback",
+        "user",
+        true,
+        "7467ed8474a3f76999d0af70068034c379de9517a6cdfe6b1f2679c10ab3f311"
+    )]
+    fn test_respondent_key(
+        #[case] secret: &str,
+        #[case] feedback_id: &str,
+        #[case] user_id: &str,
+        #[case] anonymous: bool,
+        #[case] expected: &str,
+    ) {
         // Act
-        let result = parse_command(&activity);
+        let result = respondent_key(secret, feedback_id, user_id, anonymous);
 
         // Assert
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn test_respondent_key_is_not_reused() {
+        // Act
+        let result = respondent_key("secret", "feedback", "user", true);
+
+        // Assert
+        assert_ne!(result, respondent_key("secret", "another", "user", true));
+        assert_ne!(result, respondent_key("another", "feedback", "user", true));
+        assert_ne!(
+            result,
+            respondent_key("secret", "feedback", "another", true)
+        );
+    }
 }